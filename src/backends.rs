@@ -0,0 +1,114 @@
+use crate::stats::percentile;
+use reqwest::blocking::Client;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+// Headers providers use to name the backend that served a request, checked
+// in order. Without any of them, the backend is told apart by remote address.
+const BACKEND_HEADERS: [&str; 6] = [
+    "x-served-by",
+    "x-backend",
+    "x-backend-server",
+    "x-upstream",
+    "x-server",
+    "x-node-id",
+];
+
+#[derive(Debug)]
+pub struct BackendReport {
+    probes: usize,
+    failed: usize,
+    identified_by: Option<String>,
+    backends: BTreeMap<String, Vec<Duration>>,
+}
+
+// Sends `probes` getSlot requests, each on a fresh connection so DNS and
+// the load balancer get a chance to route it elsewhere, and groups their
+// latencies by the backend that answered.
+pub fn detect(endpoint: &str, probes: usize) -> BackendReport {
+    let mut report = BackendReport {
+        probes,
+        failed: 0,
+        identified_by: None,
+        backends: BTreeMap::new(),
+    };
+    let client = match Client::builder()
+        .pool_max_idle_per_host(0)
+        .timeout(Duration::from_secs(30))
+        .build()
+    {
+        Ok(client) => client,
+        Err(_) => {
+            report.failed = probes;
+            return report;
+        }
+    };
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "getSlot" });
+
+    for _ in 0..probes {
+        let started = Instant::now();
+        let response = match client.post(endpoint).json(&request).send() {
+            Ok(response) if response.status().is_success() => response,
+            _ => {
+                report.failed += 1;
+                continue;
+            }
+        };
+        let latency = started.elapsed();
+
+        let header = BACKEND_HEADERS.iter().find_map(|name| {
+            let value = response.headers().get(*name)?.to_str().ok()?;
+            Some((*name, value.to_string()))
+        });
+        let backend = match header {
+            Some((name, value)) => {
+                report.identified_by = Some(format!("{} header", name));
+                value
+            }
+            None => match response.remote_addr() {
+                Some(addr) => addr.ip().to_string(),
+                None => "unknown".to_string(),
+            },
+        };
+        report.backends.entry(backend).or_default().push(latency);
+    }
+    report
+}
+
+pub fn display(reports: &BTreeMap<String, BackendReport>) -> String {
+    let mut out = String::new();
+    for (alias, report) in reports {
+        let identified_by = report.identified_by.as_deref().unwrap_or("remote address");
+        out.push_str(&format!(
+            "\n{} ({} probes, {} failed, backends told apart by {})\n",
+            alias, report.probes, report.failed, identified_by
+        ));
+
+        let mut medians = vec![];
+        for (backend, latencies) in &report.backends {
+            let mut sorted = latencies.clone();
+            sorted.sort();
+            let p50 = percentile(&sorted, 50.0).unwrap_or_default();
+            medians.push(p50);
+            out.push_str(&format!(
+                "  {:<24} {:>5} probes  p50 {:>10}  min {:>10}  max {:>10}\n",
+                backend,
+                sorted.len(),
+                format!("{:.2?}", p50),
+                format!("{:.2?}", sorted[0]),
+                format!("{:.2?}", sorted[sorted.len() - 1])
+            ));
+        }
+
+        match (medians.iter().min(), medians.iter().max()) {
+            (Some(fastest), Some(slowest)) if medians.len() > 1 => out.push_str(&format!(
+                "  {} backends observed, p50 spread {:.2?}\n",
+                medians.len(),
+                *slowest - *fastest
+            )),
+            _ => out.push_str(&format!("  {} backend observed\n", medians.len())),
+        }
+    }
+    out
+}
@@ -0,0 +1,100 @@
+use crate::BenchmarkResult;
+use crate::error::ErrorKind;
+use crate::methods::Method;
+use crate::protocol::ResponseInfo;
+use reqwest::blocking::Client;
+use serde_json::{Value, json};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::time::SystemTime;
+
+// Packs `size` identical read requests into one JSON-RPC batch. RpcClient
+// only sends single requests, so batches go through reqwest directly.
+#[derive(Debug)]
+pub struct Batch {
+    pub size: usize,
+    pub client: Client,
+}
+
+impl Batch {
+    // Sends one batch and records whether the endpoint answered it as a
+    // batch: an array with a result for every request.
+    pub fn run(
+        &self,
+        endpoint: &str,
+        method: Method,
+        account: &Pubkey,
+        commitment: CommitmentConfig,
+        min_context_slot: Option<u64>,
+        result: &mut BenchmarkResult,
+    ) {
+        let (request, params) = method.request(account, commitment, min_context_slot);
+        let body: Vec<Value> = (0..self.size)
+            .map(|id| {
+                let mut body = json!({ "jsonrpc": "2.0", "id": id, "method": request.to_string() });
+                if !params.is_null() {
+                    body["params"] = params.clone();
+                }
+                body
+            })
+            .collect();
+        result.set_extra("batch_size", self.size);
+
+        let sent = SystemTime::now();
+        let response = self
+            .client
+            .post(endpoint)
+            .json(&body)
+            .send()
+            .inspect(|response| {
+                result.observe(ResponseInfo::of(
+                    response.version(),
+                    response.headers(),
+                    sent,
+                    SystemTime::now(),
+                ))
+            })
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json::<Value>());
+        let response = match response {
+            Ok(response) => response,
+            Err(err) => {
+                result.set_error(ErrorKind::of_http(&err), err.to_string());
+                return;
+            }
+        };
+
+        let Some(responses) = response.as_array().filter(|r| r.len() == self.size) else {
+            result.set_extra("batch_supported", false);
+            let error = response
+                .get("error")
+                .and_then(|error| error.get("message"))
+                .and_then(Value::as_str)
+                .map(|message| format!("endpoint rejected the batch: {}", message))
+                .unwrap_or_else(|| {
+                    format!("expected {} batched responses, got {}", self.size, response)
+                });
+            result.set_error(ErrorKind::Unsupported, error);
+            return;
+        };
+        result.set_extra("batch_supported", true);
+
+        let failed: Vec<&Value> = responses
+            .iter()
+            .filter(|response| response.get("result").is_none())
+            .collect();
+        result.set_extra("batch_errors", failed.len());
+        if let Some(first) = failed.first() {
+            let error = first.get("error").unwrap_or(first);
+            result.set_error(
+                ErrorKind::Rpc,
+                format!(
+                    "{} of {} batched requests failed, first: {}",
+                    failed.len(),
+                    self.size,
+                    error
+                ),
+            );
+        }
+    }
+}
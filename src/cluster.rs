@@ -0,0 +1,24 @@
+use solana_client::rpc_client::RpcClient;
+
+// Genesis hashes of the public clusters
+const KNOWN_CLUSTERS: [(&str, &str); 3] = [
+    (
+        "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+        "mainnet-beta",
+    ),
+    ("EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG", "devnet"),
+    ("4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY", "testnet"),
+];
+
+// Names the cluster an endpoint serves from its genesis hash. Unknown hashes
+// are "custom"; None means the hash couldn't be fetched.
+pub fn detect(rpc_client: &RpcClient) -> Option<&'static str> {
+    let genesis_hash = rpc_client.get_genesis_hash().ok()?.to_string();
+    Some(
+        KNOWN_CLUSTERS
+            .iter()
+            .find(|(hash, _)| *hash == genesis_hash)
+            .map(|(_, name)| *name)
+            .unwrap_or("custom"),
+    )
+}
@@ -0,0 +1,280 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Args, Debug)]
+pub struct CompareArgs {
+    /// Summary file written with --summary-json by the baseline run
+    baseline: PathBuf,
+
+    /// Summary file written with --summary-json by the run to check
+    current: PathBuf,
+
+    /// Latency metric to compare
+    #[arg(long, value_enum, default_value_t = Metric::P95)]
+    metric: Metric,
+
+    /// Flag an endpoint as regressed when the metric grew by more than this
+    /// many percent
+    #[arg(long, default_value_t = 10.0)]
+    threshold_pct: f64,
+
+    /// How to print the diff
+    #[arg(long, value_enum, default_value_t = CompareFormat::Text)]
+    format: CompareFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Metric {
+    Mean,
+    P50,
+    P95,
+    P99,
+}
+
+impl Metric {
+    // The summary field the metric is read from
+    fn field(self) -> &'static str {
+        match self {
+            Metric::Mean => "mean_ms",
+            Metric::P50 => "p50_ms",
+            Metric::P95 => "p95_ms",
+            Metric::P99 => "p99_ms",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum CompareFormat {
+    /// Aligned table for reading
+    Text,
+    /// One structured object per endpoint, for CI
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Compared,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Serialize)]
+struct EndpointDiff {
+    endpoint: String,
+    status: Status,
+    baseline_ms: Option<f64>,
+    current_ms: Option<f64>,
+    delta_ms: Option<f64>,
+    delta_pct: Option<f64>,
+    regression: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct CompareReport {
+    metric: &'static str,
+    threshold_pct: f64,
+    regressions: usize,
+    endpoints: Vec<EndpointDiff>,
+}
+
+pub fn run(args: CompareArgs) {
+    let read = |path: &Path| match read_endpoints(path) {
+        Ok(endpoints) => endpoints,
+        Err(err) => {
+            eprintln!("Failed to read {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    };
+    let baseline = read(&args.baseline);
+    let current = read(&args.current);
+
+    let report = diff(&baseline, &current, args.metric, args.threshold_pct);
+    match args.format {
+        CompareFormat::Text => print!("{}", display(&report)),
+        CompareFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(err) => {
+                eprintln!("Failed to serialize the comparison: {}", err);
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+// Diffs the metric of every endpoint in either summary
+fn diff(
+    baseline: &Map<String, Value>,
+    current: &Map<String, Value>,
+    metric: Metric,
+    threshold_pct: f64,
+) -> CompareReport {
+    // Endpoints are matched by their summary key, the endpoint alias
+    let keys: BTreeSet<&String> = baseline.keys().chain(current.keys()).collect();
+    let value = |endpoints: &Map<String, Value>, key: &str| {
+        endpoints
+            .get(key)
+            .and_then(|summary| summary.get(metric.field()))
+            .and_then(Value::as_f64)
+    };
+    let endpoints: Vec<EndpointDiff> = keys
+        .into_iter()
+        .map(|key| {
+            let status = match (baseline.contains_key(key), current.contains_key(key)) {
+                (true, true) => Status::Compared,
+                (false, _) => Status::Added,
+                (_, false) => Status::Removed,
+            };
+            let baseline_ms = value(baseline, key);
+            let current_ms = value(current, key);
+            let delta_ms = baseline_ms.zip(current_ms).map(|(b, c)| c - b);
+            let delta_pct = baseline_ms
+                .zip(delta_ms)
+                .filter(|&(b, _)| b > 0.0)
+                .map(|(b, delta)| delta / b * 100.0);
+            EndpointDiff {
+                endpoint: key.clone(),
+                status,
+                baseline_ms,
+                current_ms,
+                delta_ms,
+                delta_pct,
+                regression: delta_pct.is_some_and(|pct| pct > threshold_pct),
+            }
+        })
+        .collect();
+
+    CompareReport {
+        metric: metric.field(),
+        threshold_pct,
+        regressions: endpoints.iter().filter(|diff| diff.regression).count(),
+        endpoints,
+    }
+}
+
+fn read_endpoints(path: &Path) -> Result<Map<String, Value>, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let report: Value =
+        serde_json::from_reader(BufReader::new(file)).map_err(|err| err.to_string())?;
+    match report.get("endpoints") {
+        Some(Value::Object(endpoints)) => Ok(endpoints.clone()),
+        _ => Err("no \"endpoints\" object; expected a --summary-json file".to_string()),
+    }
+}
+
+fn display(report: &CompareReport) -> String {
+    let width = report
+        .endpoints
+        .iter()
+        .map(|diff| diff.endpoint.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let millis = |value: Option<f64>| {
+        value
+            .map(|ms| format!("{:.2}ms", ms))
+            .unwrap_or_else(|| "N/A".to_string())
+    };
+    let mut out = format!("Comparing {}\n\n", report.metric);
+    out.push_str(&format!(
+        "{:<width$} {:>10} {:>10} {:>11} {:>9}\n",
+        "Endpoint", "Baseline", "Current", "Delta", "Delta %"
+    ));
+    for diff in &report.endpoints {
+        let verdict = match diff.status {
+            Status::Added => "added",
+            Status::Removed => "removed",
+            Status::Compared if diff.regression => "REGRESSION",
+            Status::Compared => "",
+        };
+        let line = format!(
+            "{:<width$} {:>10} {:>10} {:>11} {:>9}  {}",
+            diff.endpoint,
+            millis(diff.baseline_ms),
+            millis(diff.current_ms),
+            diff.delta_ms
+                .map(|ms| format!("{:+.2}ms", ms))
+                .unwrap_or_else(|| "N/A".to_string()),
+            diff.delta_pct
+                .map(|pct| format!("{:+.1}%", pct))
+                .unwrap_or_else(|| "N/A".to_string()),
+            verdict
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out.push_str(&format!(
+        "\n{} of {} endpoints regressed by more than {}%\n",
+        report.regressions,
+        report.endpoints.len(),
+        report.threshold_pct
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn endpoints(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(endpoints) => endpoints,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn diff_flags_growth_past_the_threshold() {
+        let baseline = endpoints(json!({
+            "a": { "p95_ms": 100.0 },
+            "b": { "p95_ms": 100.0 },
+        }));
+        let current = endpoints(json!({
+            "a": { "p95_ms": 120.0 },
+            "b": { "p95_ms": 105.0 },
+        }));
+        let report = diff(&baseline, &current, Metric::P95, 10.0);
+        assert_eq!(report.regressions, 1);
+        let a = &report.endpoints[0];
+        assert_eq!(a.status, Status::Compared);
+        assert_eq!(a.delta_ms, Some(20.0));
+        assert_eq!(a.delta_pct, Some(20.0));
+        assert!(a.regression);
+        assert!(!report.endpoints[1].regression);
+    }
+
+    #[test]
+    fn diff_marks_added_and_removed_endpoints() {
+        let baseline = endpoints(json!({ "gone": { "mean_ms": 5.0 } }));
+        let current = endpoints(json!({ "new": { "mean_ms": 5.0 } }));
+        let report = diff(&baseline, &current, Metric::Mean, 10.0);
+        let statuses: Vec<(&str, Status)> = report
+            .endpoints
+            .iter()
+            .map(|diff| (diff.endpoint.as_str(), diff.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![("gone", Status::Removed), ("new", Status::Added)]
+        );
+        assert!(report.endpoints.iter().all(|diff| diff.delta_ms.is_none()));
+        assert_eq!(report.regressions, 0);
+    }
+
+    #[test]
+    fn diff_has_no_percentage_without_a_baseline_latency() {
+        let baseline = endpoints(json!({ "a": { "p50_ms": 0.0 }, "b": { "p50_ms": null } }));
+        let current = endpoints(json!({ "a": { "p50_ms": 5.0 }, "b": { "p50_ms": 5.0 } }));
+        let report = diff(&baseline, &current, Metric::P50, 10.0);
+        assert_eq!(report.endpoints[0].delta_ms, Some(5.0));
+        assert_eq!(report.endpoints[0].delta_pct, None);
+        assert_eq!(report.endpoints[1].delta_ms, None);
+        assert_eq!(report.regressions, 0);
+    }
+}
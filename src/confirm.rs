@@ -0,0 +1,372 @@
+use crate::error::ErrorKind;
+use clap::ValueEnum;
+use serde::Serialize;
+use solana_client::pubsub_client::PubsubClient;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSignatureSubscribeConfig;
+use solana_client::rpc_response::RpcSignatureResult;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use std::time::{Duration, Instant};
+use url::Url;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmVia {
+    /// Poll getSignatureStatuses over HTTP
+    Http,
+    /// Wait for a signatureSubscribe notification
+    Websocket,
+    /// Run both and record which confirmed first
+    Both,
+}
+
+impl ConfirmVia {
+    fn uses_http(self) -> bool {
+        matches!(self, ConfirmVia::Http | ConfirmVia::Both)
+    }
+
+    fn uses_websocket(self) -> bool {
+        matches!(self, ConfirmVia::Websocket | ConfirmVia::Both)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn of(config: CommitmentConfig) -> Self {
+        match config.commitment {
+            CommitmentLevel::Processed => Commitment::Processed,
+            CommitmentLevel::Confirmed => Commitment::Confirmed,
+            CommitmentLevel::Finalized => Commitment::Finalized,
+        }
+    }
+
+    pub fn config(self) -> CommitmentConfig {
+        let commitment = match self {
+            Commitment::Processed => CommitmentLevel::Processed,
+            Commitment::Confirmed => CommitmentLevel::Confirmed,
+            Commitment::Finalized => CommitmentLevel::Finalized,
+        };
+        CommitmentConfig { commitment }
+    }
+}
+
+// First delay of the adaptive schedule, which then doubles up to the
+// configured poll interval.
+const ADAPTIVE_POLL_START: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Copy, Debug)]
+pub enum PollSchedule {
+    Fixed(Duration),
+    // Polls quickly at first, since most transactions confirm fast, then backs
+    // off towards `max` for the ones that take a while.
+    Adaptive { max: Duration },
+}
+
+impl PollSchedule {
+    // Delay before the poll following `polls` earlier ones
+    fn delay(self, polls: u32) -> Duration {
+        match self {
+            PollSchedule::Fixed(interval) => interval,
+            PollSchedule::Adaptive { max } => ADAPTIVE_POLL_START
+                .saturating_mul(2u32.saturating_pow(polls.saturating_sub(1)))
+                .min(max),
+        }
+    }
+}
+
+// Confirmation latencies are measured from the moment the transaction is sent.
+#[derive(Debug, Default, Serialize)]
+pub struct Confirmation {
+    #[serde(rename = "http_ms", serialize_with = "crate::json::optional_millis")]
+    pub http: Option<Duration>,
+    #[serde(
+        rename = "websocket_ms",
+        serialize_with = "crate::json::optional_millis"
+    )]
+    pub websocket: Option<Duration>,
+    pub confirmed_first: Option<ConfirmVia>,
+    pub websocket_error: Option<String>,
+    // getSignatureStatuses calls made, and how long HTTP polling ran for
+    pub http_polls: Option<u32>,
+    #[serde(
+        rename = "http_poll_wait_ms",
+        serialize_with = "crate::json::optional_millis"
+    )]
+    pub http_poll_wait: Option<Duration>,
+}
+
+impl Confirmation {
+    pub fn display(&self) -> String {
+        let mut out = String::new();
+        if let Some(http) = self.http {
+            out.push_str(&format!("HTTP Confirmation: {:.2?}\n", http));
+        }
+        if let Some(websocket) = self.websocket {
+            out.push_str(&format!("WebSocket Confirmation: {:.2?}\n", websocket));
+        }
+        if let (Some(polls), Some(wait)) = (self.http_polls, self.http_poll_wait) {
+            out.push_str(&format!("HTTP Polls: {} over {:.2?}\n", polls, wait));
+        }
+        if let Some(ref error) = self.websocket_error {
+            out.push_str(&format!("WebSocket Error: {}\n", error));
+        }
+        if let Some(first) = self.confirmed_first {
+            out.push_str(&format!("Confirmed First: {:?}\n", first));
+        }
+        out
+    }
+}
+
+pub struct ConfirmOutcome {
+    pub signature: Option<Signature>,
+    pub confirmation: Confirmation,
+    pub error: Option<(ErrorKind, String)>,
+}
+
+// Follows the solana CLI convention: same host, ws(s) scheme, and the next
+// port up when one is given explicitly.
+pub fn websocket_url(endpoint: &str) -> Result<String, String> {
+    let mut url = Url::parse(endpoint).map_err(|err| err.to_string())?;
+    let underivable = || format!("Cannot derive a websocket URL from {}", endpoint);
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme).map_err(|_| underivable())?;
+    if let Some(port) = url.port() {
+        let port = port.checked_add(1).ok_or_else(underivable)?;
+        url.set_port(Some(port)).map_err(|_| underivable())?;
+    }
+    Ok(url.to_string())
+}
+
+// Unsubscribing waits for the socket, which the subscription's reader thread
+// holds through a blocking read that only ends with the server's next frame.
+// For a transaction that never lands that can take forever, so the shutdown
+// runs on a detached thread instead of the timed path.
+struct Detached<T: Send + 'static>(Option<T>);
+
+impl<T: Send + 'static> Drop for Detached<T> {
+    fn drop(&mut self) {
+        if let Some(subscription) = self.0.take() {
+            std::thread::spawn(move || drop(subscription));
+        }
+    }
+}
+
+pub fn send_and_confirm(
+    endpoint: &str,
+    rpc_client: &RpcClient,
+    transaction: &Transaction,
+    via: ConfirmVia,
+    poll_schedule: PollSchedule,
+    timeout: Duration,
+) -> ConfirmOutcome {
+    let signature = transaction.signatures[0];
+    let commitment = rpc_client.commitment();
+    let mut outcome = ConfirmOutcome {
+        signature: None,
+        confirmation: Confirmation::default(),
+        error: None,
+    };
+
+    // Subscribe before sending so the notification cannot be missed
+    let mut subscription = None;
+    let mut _unsubscribe = None;
+    if via.uses_websocket() {
+        let subscribed = websocket_url(endpoint).and_then(|url| {
+            let config = RpcSignatureSubscribeConfig {
+                commitment: Some(commitment),
+                enable_received_notification: Some(false),
+            };
+            PubsubClient::signature_subscribe(&url, &signature, Some(config))
+                .map_err(|err| err.to_string())
+        });
+        match subscribed {
+            Ok((client, receiver)) => {
+                _unsubscribe = Some(Detached(Some(client)));
+                subscription = Some(receiver);
+            }
+            Err(err) if via == ConfirmVia::Websocket => {
+                outcome.error = Some((
+                    ErrorKind::Connection,
+                    format!("Failed to subscribe to signature: {}", err),
+                ));
+                return outcome;
+            }
+            Err(err) => outcome.confirmation.websocket_error = Some(err),
+        }
+    }
+
+    let sent_at = Instant::now();
+    if let Err(err) = rpc_client.send_transaction(transaction) {
+        outcome.error = Some((ErrorKind::of(&err), format!("Transaction failed: {}", err)));
+        return outcome;
+    }
+    outcome.signature = Some(signature);
+
+    let deadline = sent_at + timeout;
+    let mut http_pending = via.uses_http();
+    let mut next_poll = sent_at;
+    let mut polls = 0;
+
+    while Instant::now() < deadline {
+        if http_pending && Instant::now() >= next_poll {
+            polls += 1;
+            next_poll += poll_schedule.delay(polls);
+            if let Ok(response) = rpc_client.get_signature_statuses(&[signature])
+                && let Some(status) = response.value.into_iter().flatten().next()
+            {
+                if let Some(err) = status.err {
+                    outcome.error = Some((
+                        ErrorKind::Transaction,
+                        format!("Transaction failed: {}", err),
+                    ));
+                    return outcome;
+                }
+                if status.satisfies_commitment(commitment) {
+                    outcome.confirmation.http = Some(sent_at.elapsed());
+                    outcome.confirmation.http_poll_wait = outcome.confirmation.http;
+                    http_pending = false;
+                }
+            }
+        }
+
+        let websocket_pending = subscription.is_some() && outcome.confirmation.websocket.is_none();
+        if !http_pending && !websocket_pending {
+            break;
+        }
+
+        let wake_at = if http_pending {
+            next_poll.min(deadline)
+        } else {
+            deadline
+        };
+        let wait = wake_at.saturating_duration_since(Instant::now());
+
+        match subscription {
+            Some(ref receiver) if websocket_pending => match receiver.recv_timeout(wait) {
+                Ok(notification) => {
+                    outcome.confirmation.websocket = Some(sent_at.elapsed());
+                    if let RpcSignatureResult::ProcessedSignature(processed) = notification.value
+                        && let Some(err) = processed.err
+                    {
+                        outcome.error = Some((
+                            ErrorKind::Transaction,
+                            format!("Transaction failed: {}", err),
+                        ));
+                        return outcome;
+                    }
+                }
+                Err(err) if err.is_disconnected() => {
+                    outcome.confirmation.websocket_error =
+                        Some("Websocket subscription closed".to_string());
+                    subscription = None;
+                }
+                Err(_) => {}
+            },
+            _ => std::thread::sleep(wait),
+        }
+    }
+
+    let confirmation = &mut outcome.confirmation;
+    if via.uses_http() {
+        confirmation.http_polls = Some(polls);
+        confirmation
+            .http_poll_wait
+            .get_or_insert_with(|| sent_at.elapsed());
+    }
+    confirmation.confirmed_first = match (confirmation.http, confirmation.websocket) {
+        (Some(http), Some(websocket)) if websocket < http => Some(ConfirmVia::Websocket),
+        (Some(_), _) => Some(ConfirmVia::Http),
+        (None, Some(_)) => Some(ConfirmVia::Websocket),
+        (None, None) => {
+            outcome.error = Some((
+                ErrorKind::Timeout,
+                format!("Transaction not confirmed within {:?}", timeout),
+            ));
+            None
+        }
+    };
+
+    outcome
+}
+
+// Re-polls the signature until it reaches `required` or `timeout` passes.
+// Returns the highest commitment observed along the way.
+pub fn wait_for_commitment(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+    required: Commitment,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Option<Commitment> {
+    let deadline = Instant::now() + timeout;
+    let mut achieved = None;
+
+    loop {
+        if let Ok(response) = rpc_client.get_signature_statuses(&[*signature])
+            && let Some(status) = response.value.into_iter().flatten().next()
+        {
+            let observed = [
+                Commitment::Finalized,
+                Commitment::Confirmed,
+                Commitment::Processed,
+            ]
+            .into_iter()
+            .find(|commitment| status.satisfies_commitment(commitment.config()));
+            achieved = achieved.max(observed);
+        }
+
+        if achieved >= Some(required) || Instant::now() + poll_interval > deadline {
+            return achieved;
+        }
+        std::thread::sleep(poll_interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn websocket_url_uses_the_next_port() {
+        assert_eq!(
+            websocket_url("http://127.0.0.1:8899").unwrap(),
+            "ws://127.0.0.1:8900/"
+        );
+        assert_eq!(
+            websocket_url("https://rpc.example.com").unwrap(),
+            "wss://rpc.example.com/"
+        );
+    }
+
+    #[test]
+    fn websocket_url_rejects_the_last_port() {
+        assert!(websocket_url("http://127.0.0.1:65535").is_err());
+    }
+
+    #[test]
+    fn fixed_schedule_always_waits_the_interval() {
+        let schedule = PollSchedule::Fixed(Duration::from_millis(400));
+        assert_eq!(schedule.delay(1), Duration::from_millis(400));
+        assert_eq!(schedule.delay(10), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn adaptive_schedule_doubles_up_to_the_max() {
+        let schedule = PollSchedule::Adaptive {
+            max: Duration::from_millis(300),
+        };
+        assert_eq!(schedule.delay(1), ADAPTIVE_POLL_START);
+        assert_eq!(schedule.delay(2), ADAPTIVE_POLL_START * 2);
+        assert_eq!(schedule.delay(3), ADAPTIVE_POLL_START * 4);
+        assert_eq!(schedule.delay(50), Duration::from_millis(300));
+    }
+}
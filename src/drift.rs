@@ -0,0 +1,75 @@
+use crate::BenchmarkResult;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct BlockHeightSample {
+    pub iteration: u32,
+    // Milliseconds since the endpoint's first sample
+    pub offset_ms: f64,
+    pub block_height: u64,
+}
+
+// Block height progression as observed through a single endpoint. A node that
+// lags or rate-limits shows a depressed rate compared to its peers.
+#[derive(Debug, Serialize)]
+pub struct BlockHeightDrift {
+    pub endpoint: String,
+    pub samples: Vec<BlockHeightSample>,
+    pub blocks_per_second: Option<f64>,
+}
+
+impl BlockHeightDrift {
+    pub fn from_results(results: &[BenchmarkResult]) -> Self {
+        let endpoint = results
+            .first()
+            .map(|r| r.endpoint.clone())
+            .unwrap_or_default();
+        let first_start = results.first().map(|r| r.start_time);
+
+        let samples: Vec<BlockHeightSample> = results
+            .iter()
+            .filter_map(|result| {
+                let block_height = result.block_height?;
+                let offset = result.start_time.duration_since(first_start?);
+                Some(BlockHeightSample {
+                    iteration: result.iteration,
+                    offset_ms: offset.as_secs_f64() * 1000.0,
+                    block_height,
+                })
+            })
+            .collect();
+
+        let blocks_per_second = match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) if last.offset_ms > first.offset_ms => {
+                let advanced = last.block_height.saturating_sub(first.block_height) as f64;
+                Some(advanced / ((last.offset_ms - first.offset_ms) / 1000.0))
+            }
+            _ => None,
+        };
+
+        Self {
+            endpoint,
+            samples,
+            blocks_per_second,
+        }
+    }
+
+    pub fn display(&self) -> String {
+        let heights: Vec<String> = self
+            .samples
+            .iter()
+            .map(|s| s.block_height.to_string())
+            .collect();
+        let rate = self
+            .blocks_per_second
+            .map(|r| format!("{:.2} blocks/s", r))
+            .unwrap_or_else(|| "N/A (need at least two samples)".to_string());
+
+        format!(
+            "Endpoint: {}\nObserved Block Heights: {}\nObserved Block Rate: {}\n",
+            self.endpoint,
+            heights.join(", "),
+            rate
+        )
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use std::fmt;
+
+// Coarse failure classes used for the error breakdown in summaries
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Timeout,
+    RateLimited,
+    Connection,
+    Rpc,
+    Transaction,
+    Unsupported,
+    // A read that succeeded but failed an --expect-* check
+    Assertion,
+    Other,
+}
+
+impl ErrorKind {
+    pub fn of(err: &ClientError) -> Self {
+        if err.get_transaction_error().is_some() {
+            return ErrorKind::Transaction;
+        }
+        match err.kind() {
+            ClientErrorKind::Reqwest(err) => ErrorKind::of_http(err),
+            ClientErrorKind::Io(_) => ErrorKind::Connection,
+            ClientErrorKind::RpcError(_) => ErrorKind::Rpc,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    // For requests sent with reqwest directly rather than through RpcClient
+    pub fn of_http(err: &reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ErrorKind::Timeout
+        } else if err.status().map(|s| s.as_u16()) == Some(429) {
+            ErrorKind::RateLimited
+        } else {
+            ErrorKind::Connection
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Connection => "connection",
+            ErrorKind::Rpc => "rpc",
+            ErrorKind::Transaction => "transaction",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Assertion => "assertion",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::BenchmarkResult;
+use crate::methods::is_min_context_slot_error;
+use crate::stats::percentile;
+use serde_json::json;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::Response;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+// Delay between reads the endpoint rejected as not fresh enough yet
+const RETRY_DELAY: Duration = Duration::from_millis(10);
+
+// Measures read-after-write consistency: takes the slot the transaction
+// landed in, then reads `account` with that slot as minContextSlot until the
+// endpoint serves it. Runs after the write is confirmed and is not part of
+// the iteration's duration.
+pub fn read_after_write(
+    rpc_client: &RpcClient,
+    account: &Pubkey,
+    signature: &Signature,
+    timeout: Duration,
+    result: &mut BenchmarkResult,
+) {
+    let slot = match rpc_client.get_signature_statuses(&[*signature]) {
+        Ok(response) => match response.value.into_iter().flatten().next() {
+            Some(status) => status.slot,
+            None => {
+                result.set_extra("read_after_write_error", "write slot unknown");
+                return;
+            }
+        },
+        Err(err) => {
+            result.set_extra("read_after_write_error", err.to_string());
+            return;
+        }
+    };
+    result.set_extra("write_slot", slot);
+
+    let config = json!({
+        "commitment": rpc_client.commitment().commitment,
+        "minContextSlot": slot,
+    });
+    let started = Instant::now();
+    let mut attempts = 0u32;
+    loop {
+        attempts += 1;
+        let read = rpc_client
+            .send::<Response<u64>>(RpcRequest::GetBalance, json!([account.to_string(), config]));
+        match read {
+            Ok(_) => {
+                let elapsed = started.elapsed();
+                result.set_extra("read_after_write_ms", elapsed.as_secs_f64() * 1000.0);
+                break;
+            }
+            Err(ref err) if is_min_context_slot_error(err) && started.elapsed() < timeout => {
+                std::thread::sleep(RETRY_DELAY);
+            }
+            Err(err) => {
+                result.set_extra("read_after_write_error", err.to_string());
+                break;
+            }
+        }
+    }
+    result.set_extra("read_after_write_attempts", attempts);
+}
+
+pub fn display(aliases: &[String], endpoint_results: &[Vec<BenchmarkResult>]) -> String {
+    let width = aliases.iter().map(String::len).max().unwrap_or(0).max(8);
+    let mut out = format!(
+        "{:<width$} {:>8} {:>8} {:>10} {:>10} {:>10}\n",
+        "Endpoint", "Reads", "Failed", "Mean", "p50", "p95"
+    );
+    let mut rows: BTreeMap<&str, String> = BTreeMap::new();
+    for (alias, results) in aliases.iter().zip(endpoint_results) {
+        let mut latencies: Vec<f64> = results
+            .iter()
+            .filter_map(|result| result.extras.get("read_after_write_ms"))
+            .filter_map(|ms| ms.as_f64())
+            .collect();
+        latencies.sort_by(f64::total_cmp);
+        let failed = results
+            .iter()
+            .filter(|result| result.extras.contains_key("read_after_write_error"))
+            .count();
+        let millis = |value: Option<f64>| {
+            value
+                .map(|ms| format!("{:.2}ms", ms))
+                .unwrap_or_else(|| "N/A".to_string())
+        };
+        let mean = if latencies.is_empty() {
+            None
+        } else {
+            Some(latencies.iter().sum::<f64>() / latencies.len() as f64)
+        };
+        rows.insert(
+            alias,
+            format!(
+                "{:<width$} {:>8} {:>8} {:>10} {:>10} {:>10}\n",
+                alias,
+                latencies.len() + failed,
+                failed,
+                millis(mean),
+                millis(percentile(&latencies, 50.0)),
+                millis(percentile(&latencies, 95.0))
+            ),
+        );
+    }
+    out.extend(rows.into_values());
+    out
+}
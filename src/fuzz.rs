@@ -0,0 +1,113 @@
+use crate::BenchmarkResult;
+use crate::error::ErrorKind;
+use crate::methods::{self, Method};
+use clap::ValueEnum;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::thread;
+use std::time::Duration;
+
+// One call's outcome in the method survey
+#[derive(Debug)]
+enum Cell {
+    Latency(Duration),
+    Unsupported,
+    Failed(ErrorKind),
+    // Account methods with neither --account nor --keypair
+    NoAccount,
+}
+
+impl Cell {
+    fn display(&self) -> String {
+        match self {
+            Cell::Latency(latency) => format!("{:.2}ms", latency.as_secs_f64() * 1000.0),
+            Cell::Unsupported => "unsupported".to_string(),
+            Cell::Failed(kind) => format!("error ({})", kind),
+            Cell::NoAccount => "no account".to_string(),
+        }
+    }
+}
+
+pub struct Survey {
+    methods: Vec<Method>,
+    // Per endpoint, one cell per method
+    cells: Vec<Vec<Cell>>,
+}
+
+// Calls every read method once against every endpoint, the endpoints in
+// parallel, to show what each provider serves and how fast.
+pub fn run(endpoints: &[String], account: Option<Pubkey>, timeout: Duration) -> Survey {
+    let methods: Vec<Method> = Method::value_variants()
+        .iter()
+        .copied()
+        .filter(|method| method.is_read_only())
+        .collect();
+    let cells = thread::scope(|scope| {
+        let handles: Vec<_> = endpoints
+            .iter()
+            .map(|endpoint| {
+                let methods = &methods;
+                scope.spawn(move || {
+                    let rpc_client = RpcClient::new_with_timeout(endpoint.clone(), timeout);
+                    methods
+                        .iter()
+                        .map(|&method| survey(endpoint, &rpc_client, method, account))
+                        .collect()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    Survey { methods, cells }
+}
+
+fn survey(endpoint: &str, rpc_client: &RpcClient, method: Method, account: Option<Pubkey>) -> Cell {
+    let account = match account {
+        Some(account) => account,
+        None if method.reads_account() => return Cell::NoAccount,
+        None => Pubkey::default(),
+    };
+    let mut result = BenchmarkResult::new(endpoint.to_string(), 0);
+    methods::run_read(method, rpc_client, &account, None, &mut result);
+    result.complete();
+    match result.error_kind {
+        None => Cell::Latency(result.duration().unwrap_or_default()),
+        Some(ErrorKind::Unsupported) => Cell::Unsupported,
+        Some(kind) => Cell::Failed(kind),
+    }
+}
+
+// Method x endpoint matrix
+pub fn display(survey: &Survey, aliases: &[String]) -> String {
+    let names: Vec<String> = survey.methods.iter().map(|method| method.name()).collect();
+    let method_width = names.iter().map(String::len).max().unwrap_or(0).max(6);
+    let widths: Vec<usize> = aliases
+        .iter()
+        .zip(&survey.cells)
+        .map(|(alias, cells)| {
+            cells
+                .iter()
+                .map(|cell| cell.display().len())
+                .chain([alias.len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = format!("{:<method_width$}", "Method");
+    for (alias, width) in aliases.iter().zip(&widths) {
+        out.push_str(&format!("  {:>width$}", alias));
+    }
+    out.push('\n');
+    for (row, name) in names.iter().enumerate() {
+        out.push_str(&format!("{:<method_width$}", name));
+        for (cells, width) in survey.cells.iter().zip(&widths) {
+            out.push_str(&format!("  {:>width$}", cells[row].display()));
+        }
+        out.push('\n');
+    }
+    out
+}
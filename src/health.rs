@@ -0,0 +1,83 @@
+use clap::ValueEnum;
+use reqwest::blocking::Client;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use std::fmt;
+use std::time::Duration;
+
+// Ways to tell whether an endpoint is reachable before benchmarking it.
+// Providers differ in which of these they answer properly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HealthProbe {
+    /// The getHealth RPC call
+    GetHealth,
+    /// The getVersion RPC call
+    GetVersion,
+    /// A plain HTTP GET of the endpoint URL
+    RootPing,
+}
+
+impl HealthProbe {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HealthProbe::GetHealth => "get-health",
+            HealthProbe::GetVersion => "get-version",
+            HealthProbe::RootPing => "root-ping",
+        }
+    }
+
+    fn probe(self, endpoint: &str, timeout: Duration) -> Result<(), String> {
+        match self {
+            HealthProbe::GetHealth => RpcClient::new_with_timeout(endpoint.to_string(), timeout)
+                .get_health()
+                .map_err(|err| err.to_string()),
+            HealthProbe::GetVersion => RpcClient::new_with_timeout(endpoint.to_string(), timeout)
+                .get_version()
+                .map(|_| ())
+                .map_err(|err| err.to_string()),
+            // Many RPC roots reject GET with a 4xx, which still proves the
+            // endpoint is up; only server errors count against it
+            HealthProbe::RootPing => {
+                let client = Client::builder()
+                    .timeout(timeout)
+                    .build()
+                    .map_err(|err| err.to_string())?;
+                let response = client.get(endpoint).send().map_err(|err| err.to_string())?;
+                if response.status().is_server_error() {
+                    Err(format!("HTTP {}", response.status()))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for HealthProbe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Tries `preferred` first and falls back to the other probes, so an
+// endpoint is only unreachable if none of them succeed. Returns the probe
+// that succeeded, or every probe's failure.
+pub fn check(
+    endpoint: &str,
+    preferred: HealthProbe,
+    timeout: Duration,
+) -> Result<HealthProbe, Vec<String>> {
+    let fallbacks = HealthProbe::value_variants()
+        .iter()
+        .copied()
+        .filter(|&probe| probe != preferred);
+    let mut failures = vec![];
+    for probe in std::iter::once(preferred).chain(fallbacks) {
+        match probe.probe(endpoint, timeout) {
+            Ok(()) => return Ok(probe),
+            Err(err) => failures.push(format!("{}: {}", probe, err)),
+        }
+    }
+    Err(failures)
+}
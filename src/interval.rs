@@ -0,0 +1,402 @@
+use crate::sparkline;
+use crate::stats::percentile;
+use crate::summary::{self, EndpointSummary, Sample};
+use crate::{BenchmarkConfig, BenchmarkResult, run_iteration, unsupported_iteration};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::signature::Keypair;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A run without a cycle limit never reaches the summary at the end, so it
+// keeps only this many recent cycles per endpoint and prints their summary
+// each time that many more have run
+const ROLLING_SUMMARY_CYCLES: usize = 100;
+
+// How interval mode schedules cycles and what it reports after each
+#[derive(Clone, Copy, Debug)]
+pub struct IntervalOptions {
+    pub interval: Duration,
+    // 0 runs until interrupted
+    pub cycles: u32,
+    pub breaker: Option<BreakerConfig>,
+    pub sparkline: Option<SparklineConfig>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BreakerConfig {
+    pub threshold: u32,
+    pub cooldown: u32,
+}
+
+// Shows each endpoint's p50 over the last `cycles` cycles, every point
+// taken over the `window` cycles ending there
+#[derive(Clone, Copy, Debug)]
+pub struct SparklineConfig {
+    pub cycles: usize,
+    pub window: usize,
+}
+
+// Per-cycle latency in milliseconds, None where the cycle failed or was
+// skipped, kept just long enough to draw the sparkline
+#[derive(Debug)]
+struct History {
+    config: SparklineConfig,
+    latencies: VecDeque<Option<f64>>,
+}
+
+impl History {
+    fn new(config: SparklineConfig) -> Self {
+        History {
+            config,
+            latencies: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, latency_ms: Option<f64>) {
+        self.latencies.push_back(latency_ms);
+        if self.latencies.len() > self.config.cycles + self.config.window - 1 {
+            self.latencies.pop_front();
+        }
+    }
+
+    // Rolling p50 for each of the last `cycles` cycles
+    fn rolling_p50(&self) -> Vec<Option<f64>> {
+        let latencies: Vec<Option<f64>> = self.latencies.iter().copied().collect();
+        (0..latencies.len())
+            .rev()
+            .take(self.config.cycles)
+            .rev()
+            .map(|end| {
+                let start = (end + 1).saturating_sub(self.config.window);
+                let mut window: Vec<f64> =
+                    latencies[start..=end].iter().flatten().copied().collect();
+                window.sort_by(f64::total_cmp);
+                percentile(&window, 50.0)
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl fmt::Display for BreakerState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half-open",
+        })
+    }
+}
+
+// Trips open after `threshold` consecutive failed cycles and skips the
+// endpoint for `cooldown` cycles. The next cycle is a half-open trial: a
+// success closes the breaker again, a failure reopens it.
+#[derive(Debug)]
+struct Breaker {
+    config: Option<BreakerConfig>,
+    state: BreakerState,
+    consecutive_failures: u32,
+    cooldown_remaining: u32,
+}
+
+impl Breaker {
+    fn new(config: Option<BreakerConfig>) -> Self {
+        Breaker {
+            config,
+            state: BreakerState::Closed,
+            consecutive_failures: 0,
+            cooldown_remaining: 0,
+        }
+    }
+
+    // Returns whether the endpoint should be benchmarked this cycle
+    fn begin_cycle(&mut self) -> bool {
+        match self.state {
+            BreakerState::Open if self.cooldown_remaining > 0 => {
+                self.cooldown_remaining -= 1;
+                false
+            }
+            BreakerState::Open => {
+                self.state = BreakerState::HalfOpen;
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn record(&mut self, success: bool) {
+        let Some(config) = self.config else {
+            return;
+        };
+        if success {
+            self.state = BreakerState::Closed;
+            self.consecutive_failures = 0;
+            return;
+        }
+        self.consecutive_failures += 1;
+        if self.state == BreakerState::HalfOpen || self.consecutive_failures >= config.threshold {
+            self.state = BreakerState::Open;
+            self.cooldown_remaining = config.cooldown;
+        }
+    }
+}
+
+// Benchmarks every endpoint once per cycle, starting a new cycle every
+// `interval`, for `cycles` cycles. Returns the results grouped per endpoint.
+// With no cycle limit, memory stays bounded: only the latest
+// ROLLING_SUMMARY_CYCLES results per endpoint are kept.
+pub fn run(
+    endpoints: &[String],
+    aliases: &[String],
+    keypair: Option<&Keypair>,
+    config: &BenchmarkConfig,
+    options: IntervalOptions,
+) -> Vec<Vec<BenchmarkResult>> {
+    let IntervalOptions {
+        interval,
+        cycles,
+        breaker,
+        sparkline,
+    } = options;
+    let clients: Vec<RpcClient> = endpoints
+        .iter()
+        .map(|endpoint| config.retry.client(endpoint))
+        .collect();
+    let unsupported: Vec<Option<String>> = clients
+        .iter()
+        .map(|client| config.unsupported_on(client))
+        .collect();
+    let mut breakers: Vec<Breaker> = endpoints.iter().map(|_| Breaker::new(breaker)).collect();
+    let mut results: Vec<VecDeque<BenchmarkResult>> =
+        endpoints.iter().map(|_| VecDeque::new()).collect();
+    let mut histories: Vec<History> = match sparkline {
+        Some(config) => endpoints.iter().map(|_| History::new(config)).collect(),
+        None => vec![],
+    };
+    let endpoint_count = endpoints.len() as u64;
+    let alias_width = aliases.iter().map(|alias| alias.len()).max().unwrap_or(0);
+
+    let started = Instant::now();
+    let mut cycle = 0u32;
+    while cycles == 0 || cycle < cycles {
+        // Cycles follow a fixed schedule; one that overruns starts the next
+        // immediately.
+        let wait = (started + interval * cycle).saturating_duration_since(Instant::now());
+        thread::sleep(wait);
+
+        let runnable: Vec<bool> = breakers.iter_mut().map(Breaker::begin_cycle).collect();
+        let outcomes: Vec<Option<BenchmarkResult>> = thread::scope(|scope| {
+            let handles: Vec<_> = endpoints
+                .iter()
+                .zip(&clients)
+                .zip(&unsupported)
+                .zip(&runnable)
+                .enumerate()
+                .map(|(index, (((endpoint, client), unsupported), &run))| {
+                    let lamports = cycle as u64 * endpoint_count + index as u64 + 1;
+                    run.then(|| {
+                        scope.spawn(move || match unsupported {
+                            Some(reason) => unsupported_iteration(endpoint, cycle, reason),
+                            None => config.retry.run(endpoint, client, |client| {
+                                run_iteration(endpoint, client, keypair, config, cycle, lamports)
+                            }),
+                        })
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.map(|handle| handle.join().unwrap()))
+                .collect()
+        });
+
+        println!("\nCycle #{}", cycle + 1);
+        for (index, outcome) in outcomes.into_iter().enumerate() {
+            let breaker = &mut breakers[index];
+            let state = breaker.state;
+            let latency_ms = outcome
+                .as_ref()
+                .filter(|result| result.error.is_none())
+                .and_then(|result| result.duration_ms);
+            if let Some(history) = histories.get_mut(index) {
+                history.record(latency_ms);
+            }
+            let status = match outcome {
+                Some(mut result) => {
+                    config.label(&mut result);
+                    breaker.record(result.error.is_none());
+                    let status = match result.error_kind {
+                        Some(kind) => format!("FAILED ({})", kind),
+                        None => format!("OK {:.2?}", result.duration().unwrap_or_default()),
+                    };
+                    results[index].push_back(result);
+                    if cycles == 0 && results[index].len() > ROLLING_SUMMARY_CYCLES {
+                        results[index].pop_front();
+                    }
+                    status
+                }
+                None => "skipped".to_string(),
+            };
+            if breaker.config.is_some() {
+                let transition = if breaker.state == state {
+                    state.to_string()
+                } else {
+                    format!("{} -> {}", state, breaker.state)
+                };
+                println!(
+                    "  {:<alias_width$}  {:<24} breaker: {}",
+                    aliases[index], status, transition
+                );
+            } else {
+                println!("  {:<alias_width$}  {}", aliases[index], status);
+            }
+        }
+        if let Some(sparkline) = sparkline {
+            println!(
+                "  p50 trend (last {} cycles, {}-cycle window):",
+                sparkline.cycles, sparkline.window
+            );
+            for (alias, history) in aliases.iter().zip(&histories) {
+                let points = history.rolling_p50();
+                let latest = points
+                    .last()
+                    .copied()
+                    .flatten()
+                    .map(|ms| format!("{:.2}ms", ms))
+                    .unwrap_or_else(|| "N/A".to_string());
+                println!(
+                    "  {:<alias_width$}  {:<width$}  {}",
+                    alias,
+                    sparkline::render(&points),
+                    latest,
+                    width = sparkline.cycles
+                );
+            }
+        }
+        cycle += 1;
+
+        if cycles == 0 && (cycle as usize).is_multiple_of(ROLLING_SUMMARY_CYCLES) {
+            let summaries: BTreeMap<String, EndpointSummary> = aliases
+                .iter()
+                .zip(endpoints)
+                .zip(&results)
+                .map(|((alias, endpoint), results)| {
+                    let samples: Vec<Sample> = results.iter().map(Sample::from).collect();
+                    (
+                        alias.clone(),
+                        EndpointSummary::from_samples(endpoint, &samples),
+                    )
+                })
+                .collect();
+            println!("\nSummary of the last {} cycles:\n", ROLLING_SUMMARY_CYCLES);
+            print!("{}", summary::display_table(&summaries));
+        }
+    }
+
+    results.into_iter().map(Vec::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> Breaker {
+        Breaker::new(Some(BreakerConfig {
+            threshold: 2,
+            cooldown: 2,
+        }))
+    }
+
+    #[test]
+    fn breaker_opens_after_consecutive_failures_and_skips_the_cooldown() {
+        let mut breaker = breaker();
+        assert!(breaker.begin_cycle());
+        breaker.record(false);
+        assert_eq!(breaker.state, BreakerState::Closed);
+        assert!(breaker.begin_cycle());
+        breaker.record(false);
+        assert_eq!(breaker.state, BreakerState::Open);
+        assert!(!breaker.begin_cycle());
+        assert!(!breaker.begin_cycle());
+        assert!(breaker.begin_cycle());
+        assert_eq!(breaker.state, BreakerState::HalfOpen);
+    }
+
+    #[test]
+    fn breaker_closes_on_a_successful_trial_and_reopens_on_a_failed_one() {
+        let mut breaker = breaker();
+        breaker.record(false);
+        breaker.record(false);
+        while !breaker.begin_cycle() {}
+        breaker.record(true);
+        assert_eq!(breaker.state, BreakerState::Closed);
+        assert_eq!(breaker.consecutive_failures, 0);
+
+        breaker.record(false);
+        breaker.record(false);
+        while !breaker.begin_cycle() {}
+        // A single failure in the trial is enough to reopen
+        breaker.record(false);
+        assert_eq!(breaker.state, BreakerState::Open);
+        assert_eq!(breaker.cooldown_remaining, 2);
+    }
+
+    #[test]
+    fn breaker_without_a_config_never_opens() {
+        let mut breaker = Breaker::new(None);
+        for _ in 0..10 {
+            assert!(breaker.begin_cycle());
+            breaker.record(false);
+        }
+        assert_eq!(breaker.state, BreakerState::Closed);
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let mut breaker = breaker();
+        breaker.record(false);
+        breaker.record(true);
+        breaker.record(false);
+        assert_eq!(breaker.state, BreakerState::Closed);
+    }
+
+    fn history(cycles: usize, window: usize, latencies: &[Option<f64>]) -> History {
+        let mut history = History::new(SparklineConfig { cycles, window });
+        for &latency in latencies {
+            history.record(latency);
+        }
+        history
+    }
+
+    #[test]
+    fn rolling_p50_covers_the_last_cycles_over_their_windows() {
+        let history = history(
+            3,
+            2,
+            &[Some(1.0), Some(2.0), Some(3.0), Some(4.0), Some(5.0)],
+        );
+        // Only cycles + window - 1 latencies are kept
+        assert_eq!(history.latencies.len(), 4);
+        assert_eq!(history.rolling_p50(), vec![Some(2.0), Some(3.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn rolling_p50_skips_failed_cycles_within_a_window() {
+        let history = history(3, 2, &[Some(1.0), None, None, Some(4.0)]);
+        assert_eq!(history.rolling_p50(), vec![Some(1.0), None, Some(4.0)]);
+    }
+
+    #[test]
+    fn rolling_p50_starts_with_partial_windows() {
+        let history = history(4, 3, &[Some(3.0), Some(1.0)]);
+        assert_eq!(history.rolling_p50(), vec![Some(3.0), Some(1.0)]);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::confirm::Commitment;
+use crate::error::ErrorKind;
+use crate::{BenchmarkConfig, BenchmarkResult};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+const PHASES: [&str; 4] = ["blockhash", "simulate", "send", "confirm"];
+const BAR_WIDTH: usize = 40;
+
+// How long each phase of one transfer took. A phase is missing when the
+// journey failed before reaching it.
+#[derive(Debug, Default, Serialize)]
+pub struct Journey {
+    #[serde(
+        rename = "blockhash_ms",
+        serialize_with = "crate::json::optional_millis"
+    )]
+    pub blockhash: Option<Duration>,
+    #[serde(
+        rename = "simulate_ms",
+        serialize_with = "crate::json::optional_millis"
+    )]
+    pub simulate: Option<Duration>,
+    #[serde(rename = "send_ms", serialize_with = "crate::json::optional_millis")]
+    pub send: Option<Duration>,
+    #[serde(rename = "confirm_ms", serialize_with = "crate::json::optional_millis")]
+    pub confirm: Option<Duration>,
+}
+
+impl Journey {
+    fn phases(&self) -> [Option<Duration>; 4] {
+        [self.blockhash, self.simulate, self.send, self.confirm]
+    }
+
+    pub fn display(&self) -> String {
+        PHASES
+            .iter()
+            .zip(self.phases())
+            .filter_map(|(name, duration)| {
+                duration.map(|duration| format!("Journey {}: {:.2?}\n", name, duration))
+            })
+            .collect()
+    }
+}
+
+// Runs a transfer as four separately timed phases: fetch a blockhash,
+// simulate, send, then poll until the required commitment is reached.
+pub fn run(
+    rpc_client: &RpcClient,
+    keypair: &Keypair,
+    instruction: Instruction,
+    config: &BenchmarkConfig,
+    result: &mut BenchmarkResult,
+) {
+    let journey = result.journey.insert(Journey::default());
+
+    let started = Instant::now();
+    let blockhash = rpc_client.get_latest_blockhash();
+    journey.blockhash = Some(started.elapsed());
+    let blockhash = match blockhash {
+        Ok(blockhash) => blockhash,
+        Err(err) => {
+            result.set_error(
+                ErrorKind::of(&err),
+                format!("Failed to get blockhash: {}", err),
+            );
+            return;
+        }
+    };
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&keypair.pubkey()),
+        &[keypair],
+        blockhash,
+    );
+
+    let started = Instant::now();
+    let simulated = rpc_client.simulate_transaction(&transaction);
+    journey.simulate = Some(started.elapsed());
+    match simulated {
+        Ok(response) => {
+            if let Some(err) = response.value.err {
+                result.set_error(
+                    ErrorKind::Transaction,
+                    format!("Simulation failed: {}", err),
+                );
+                return;
+            }
+        }
+        Err(err) => {
+            result.set_error(ErrorKind::of(&err), format!("Simulation failed: {}", err));
+            return;
+        }
+    }
+
+    let started = Instant::now();
+    let sent = rpc_client.send_transaction(&transaction);
+    journey.send = Some(started.elapsed());
+    let signature = match sent {
+        Ok(signature) => signature,
+        Err(err) => {
+            result.set_error(ErrorKind::of(&err), format!("Transaction failed: {}", err));
+            return;
+        }
+    };
+    result.set_transaction_signature(signature);
+
+    let required = config
+        .require_commitment
+        .unwrap_or_else(|| Commitment::of(rpc_client.commitment()));
+    let started = Instant::now();
+    let achieved = crate::confirm::wait_for_commitment(
+        rpc_client,
+        &signature,
+        required,
+        config.poll_interval,
+        config.confirm_timeout,
+    );
+    result.achieved_commitment = achieved;
+    if achieved < Some(required) {
+        result.set_error(
+            ErrorKind::Timeout,
+            format!(
+                "Transaction did not reach {:?} commitment within {:?}",
+                required, config.confirm_timeout
+            ),
+        );
+        return;
+    }
+    if let Some(ref mut journey) = result.journey {
+        journey.confirm = Some(started.elapsed());
+    }
+}
+
+// Mean time per phase over an endpoint's completed journeys
+#[derive(Debug)]
+pub struct LatencyBudget {
+    journeys: usize,
+    phases: [Duration; 4],
+}
+
+impl LatencyBudget {
+    pub fn from_results(results: &[BenchmarkResult]) -> Self {
+        let completed: Vec<&Journey> = results
+            .iter()
+            .filter(|result| result.error.is_none())
+            .filter_map(|result| result.journey.as_ref())
+            .collect();
+        let mut phases = [Duration::ZERO; 4];
+        for journey in &completed {
+            for (total, phase) in phases.iter_mut().zip(journey.phases()) {
+                *total += phase.unwrap_or_default();
+            }
+        }
+        if !completed.is_empty() {
+            for total in &mut phases {
+                *total /= completed.len() as u32;
+            }
+        }
+        LatencyBudget {
+            journeys: completed.len(),
+            phases,
+        }
+    }
+}
+
+pub fn display(budgets: &BTreeMap<String, LatencyBudget>) -> String {
+    let mut out = String::new();
+    for (alias, budget) in budgets {
+        out.push_str(&format!(
+            "\n{} ({} completed journeys)\n",
+            alias, budget.journeys
+        ));
+        let total: Duration = budget.phases.iter().sum();
+        if total.is_zero() {
+            out.push_str("  No completed journeys\n");
+            continue;
+        }
+        for (name, phase) in PHASES.iter().zip(budget.phases) {
+            let share = phase.as_secs_f64() / total.as_secs_f64();
+            let bar = "#".repeat((share * BAR_WIDTH as f64).round() as usize);
+            out.push_str(&format!(
+                "  {:<10} {:>10} {:>6.1}%  {}\n",
+                name,
+                format!("{:.2?}", phase),
+                share * 100.0,
+                bar
+            ));
+        }
+    }
+    out
+}
@@ -0,0 +1,88 @@
+use crate::BenchmarkResult;
+use crate::drift::BlockHeightDrift;
+use crate::metadata::RunMetadata;
+use crate::time_format::{self, TimeFormat};
+use crate::urls;
+use serde::{Serialize, Serializer};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+#[derive(Serialize)]
+pub struct JsonReport<'a> {
+    pub metadata: &'a RunMetadata,
+    pub results: &'a [BenchmarkResult],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub control_results: &'a [BenchmarkResult],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub block_height_drift: &'a [BlockHeightDrift],
+}
+
+pub fn write<T: Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
+pub fn system_time<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+    match TimeFormat::current() {
+        TimeFormat::Unix => serializer.serialize_f64(time_format::unix_seconds(*time)),
+        _ => serializer.serialize_str(&BenchmarkResult::format_system_time(*time)),
+    }
+}
+
+pub fn optional_system_time<S: Serializer>(
+    time: &Option<SystemTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => system_time(time, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn optional_display<T: Display, S: Serializer>(
+    value: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn optional_millis<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_f64(duration.as_secs_f64() * 1000.0),
+        None => serializer.serialize_none(),
+    }
+}
+
+// Endpoint lists in the dumped config, with credentials masked
+pub fn redacted_endpoints<S: Serializer>(
+    endpoints: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match endpoints {
+        Some(endpoints) => {
+            serializer.collect_seq(endpoints.split(',').map(|e| urls::redact(e.trim())))
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn redacted_endpoint<S: Serializer>(
+    endpoint: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match endpoint {
+        Some(endpoint) => serializer.serialize_str(&urls::redact(endpoint)),
+        None => serializer.serialize_none(),
+    }
+}
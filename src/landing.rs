@@ -0,0 +1,76 @@
+use crate::BenchmarkResult;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use std::collections::BTreeMap;
+
+// getSignatureStatuses accepts at most this many signatures per call
+const MAX_SIGNATURES_PER_CALL: usize = 256;
+
+#[derive(Debug, Default)]
+pub struct LandingReport {
+    // Transactions the endpoint accepted, i.e. returned a signature for
+    accepted: usize,
+    finalized: usize,
+    // Signatures whose status couldn't be fetched
+    unknown: usize,
+}
+
+// Re-checks every accepted transaction of one endpoint and records on each
+// result whether it finalized.
+pub fn verify(endpoint: &str, results: &mut [BenchmarkResult]) -> LandingReport {
+    let rpc_client = RpcClient::new(endpoint.to_string());
+    let mut report = LandingReport::default();
+    let mut accepted: Vec<&mut BenchmarkResult> = results
+        .iter_mut()
+        .filter(|result| result.transaction_signature.is_some())
+        .collect();
+    report.accepted = accepted.len();
+
+    for chunk in accepted.chunks_mut(MAX_SIGNATURES_PER_CALL) {
+        let signatures: Vec<Signature> = chunk
+            .iter()
+            .filter_map(|result| result.transaction_signature)
+            .collect();
+        let statuses = match rpc_client.get_signature_statuses_with_history(&signatures) {
+            Ok(response) => response.value,
+            Err(_) => {
+                report.unknown += chunk.len();
+                continue;
+            }
+        };
+        for (result, status) in chunk.iter_mut().zip(statuses) {
+            let landed = status.is_some_and(|status| {
+                status.err.is_none() && status.satisfies_commitment(CommitmentConfig::finalized())
+            });
+            if landed {
+                report.finalized += 1;
+            }
+            result.set_extra("landed", landed);
+        }
+    }
+    report
+}
+
+pub fn display(reports: &BTreeMap<String, LandingReport>) -> String {
+    let width = reports.keys().map(String::len).max().unwrap_or(0).max(8);
+    let mut out = format!(
+        "{:<width$} {:>9} {:>10} {:>8} {:>13}\n",
+        "Endpoint", "Accepted", "Finalized", "Unknown", "Landing Rate"
+    );
+    for (alias, report) in reports {
+        let rate = if report.accepted == 0 {
+            "N/A".to_string()
+        } else {
+            format!(
+                "{:.1}%",
+                report.finalized as f64 / report.accepted as f64 * 100.0
+            )
+        };
+        out.push_str(&format!(
+            "{:<width$} {:>9} {:>10} {:>8} {:>13}\n",
+            alias, report.accepted, report.finalized, report.unknown, rate
+        ));
+    }
+    out
+}
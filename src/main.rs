@@ -1,54 +1,163 @@
-use chrono::{DateTime, Local};
-use clap::Parser;
+mod backends;
+mod batch;
+mod cluster;
+mod compare;
+mod confirm;
+mod drift;
+mod error;
+mod freshness;
+mod fuzz;
+mod health;
+mod interval;
+mod journey;
+mod json;
+mod landing;
+mod merge;
+mod metadata;
+mod methods;
+mod pool;
+mod presigned;
+mod protocol;
+mod ramp;
+mod recipients;
+mod regions;
+mod retry;
+mod schedule;
+mod solana_config;
+mod sparkline;
+mod stats;
+mod stream;
+mod summary;
+mod sweep;
+mod time_format;
+mod urls;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use confirm::{Commitment, ConfirmVia, Confirmation, PollSchedule};
+use error::ErrorKind;
+use methods::Method;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::Serialize;
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::signature::{Signature, read_keypair_file};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, read_keypair_file};
 use solana_sdk::signer::Signer;
-use solana_sdk::system_instruction;
 use solana_sdk::transaction::Transaction;
-use std::path::PathBuf;
-use std::sync::Arc;
-use std::thread;
-use std::time::{Instant, SystemTime};
+use solana_system_interface::instruction as system_instruction;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Barrier};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+use time_format::TimeFormat;
 
-#[derive(Debug)]
+// Fields serialize in declaration order; anything dynamic goes into `extras`,
+// whose BTreeMap keeps archived JSON key order stable across runs.
+#[derive(Debug, Serialize)]
 struct BenchmarkResult {
     endpoint: String,
+    cluster: Option<&'static str>,
+    // --benchmark-name, so streamed results carry it too
+    #[serde(skip_serializing_if = "Option::is_none")]
+    benchmark: Option<String>,
+    // Protocol the response came back over, e.g. "HTTP/2"
+    http_version: Option<&'static str>,
+    // The response's Date header, and how far the server's clock runs
+    // ahead of ours by it
+    #[serde(serialize_with = "json::optional_system_time")]
+    server_date: Option<SystemTime>,
+    server_clock_offset_ms: Option<f64>,
+    iteration: u32,
+    #[serde(skip)]
     start_time: Instant,
+    #[serde(rename = "start_time", serialize_with = "json::system_time")]
     start_system_time: SystemTime,
+    #[serde(skip)]
     end_time: Option<Instant>,
+    #[serde(rename = "end_time", serialize_with = "json::optional_system_time")]
     end_system_time: Option<SystemTime>,
+    duration_ms: Option<f64>,
+    normalized_duration_ms: Option<f64>,
     block_height: Option<u64>,
     error: Option<String>,
+    error_kind: Option<ErrorKind>,
+    // Retries made before the iteration failed for good, if any were
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failed_after_retries: Option<u32>,
+    #[serde(serialize_with = "json::optional_display")]
     transaction_signature: Option<Signature>,
     transaction_block_height: Option<u64>,
+    confirmation: Option<Confirmation>,
+    achieved_commitment: Option<Commitment>,
+    journey: Option<journey::Journey>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    tags: BTreeMap<String, String>,
+    extras: BTreeMap<String, serde_json::Value>,
 }
 
 impl BenchmarkResult {
-    fn new(endpoint: String) -> Self {
+    // Starting a result forgets any response recorded on this thread before
+    // it, so complete() only picks up the result's own
+    fn new(endpoint: String, iteration: u32) -> Self {
+        protocol::take_last_response();
         Self {
             endpoint,
+            cluster: None,
+            benchmark: None,
+            http_version: None,
+            server_date: None,
+            server_clock_offset_ms: None,
+            iteration,
             start_time: Instant::now(),
             start_system_time: SystemTime::now(),
             end_time: None,
             end_system_time: None,
+            duration_ms: None,
+            normalized_duration_ms: None,
             block_height: None,
             error: None,
+            error_kind: None,
+            failed_after_retries: None,
             transaction_signature: None,
             transaction_block_height: None,
+            confirmation: None,
+            achieved_commitment: None,
+            journey: None,
+            tags: BTreeMap::new(),
+            extras: BTreeMap::new(),
         }
     }
 
     fn complete(&mut self) {
         self.end_time = Some(Instant::now());
         self.end_system_time = Some(SystemTime::now());
+        self.duration_ms = self.duration().map(|d| d.as_secs_f64() * 1000.0);
+        // Batches bypass RpcClient and record their response themselves
+        if let Some(info) = protocol::take_last_response() {
+            self.observe(info);
+        }
     }
 
-    fn duration(&self) -> Option<std::time::Duration> {
+    fn duration(&self) -> Option<Duration> {
         self.end_time.map(|end| end.duration_since(self.start_time))
     }
 
-    fn set_error(&mut self, error: String) {
+    // Subtracts the control endpoint's concurrent measurement, leaving the
+    // latency attributable to this endpoint rather than to cluster state.
+    fn normalize_against(&mut self, control: &BenchmarkResult) {
+        if self.error.is_some() || control.error.is_some() {
+            return;
+        }
+        if let (Some(raw), Some(baseline)) = (self.duration_ms, control.duration_ms) {
+            self.normalized_duration_ms = Some(raw - baseline);
+        }
+    }
+
+    fn set_error(&mut self, kind: ErrorKind, error: String) {
         self.error = Some(error);
+        self.error_kind = Some(kind);
     }
 
     fn set_block_height(&mut self, height: u64) {
@@ -63,9 +172,18 @@ impl BenchmarkResult {
         self.transaction_block_height = Some(height);
     }
 
+    fn observe(&mut self, info: protocol::ResponseInfo) {
+        self.http_version = Some(info.version);
+        self.server_date = info.server_date;
+        self.server_clock_offset_ms = info.clock_offset_ms;
+    }
+
+    fn set_extra(&mut self, key: &str, value: impl Into<serde_json::Value>) {
+        self.extras.insert(key.to_string(), value.into());
+    }
+
     fn format_system_time(time: SystemTime) -> String {
-        let datetime: DateTime<Local> = time.into();
-        datetime.format("%Y-%m-%d %H:%M:%S.%3f %Z").to_string()
+        TimeFormat::current().format(time)
     }
 
     fn display(&self) -> String {
@@ -74,14 +192,49 @@ impl BenchmarkResult {
             .map(|d| format!("{:.2?}", d))
             .unwrap_or_else(|| "N/A".to_string());
 
-        let status = if let Some(height) = self.block_height {
+        let status = if let (Some(retries), Some(error)) = (self.failed_after_retries, &self.error)
+        {
+            format!("Failed after {} retries: {}", retries, error)
+        } else if let Some(height) = self.block_height {
             format!("Success (Block Height: {})", height)
         } else if let Some(ref error) = self.error {
             format!("Error: {}", error)
+        } else if self.end_time.is_some() {
+            "Success".to_string()
         } else {
             "Unknown Status".to_string()
         };
 
+        let cluster = self
+            .cluster
+            .map(|cluster| format!("Cluster: {}\n", cluster))
+            .unwrap_or_default();
+
+        let http_version = self
+            .http_version
+            .map(|version| format!("HTTP Version: {}\n", version))
+            .unwrap_or_default();
+
+        let server_date = match (self.server_date, self.server_clock_offset_ms) {
+            (Some(date), Some(offset)) => format!(
+                "Server Date: {} (clock offset {:+.0}ms)\n",
+                Self::format_system_time(date),
+                offset
+            ),
+            _ => String::new(),
+        };
+
+        let tags = if self.tags.is_empty() {
+            String::new()
+        } else {
+            let tags: Vec<String> = self
+                .tags
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            format!("Tags: {}\n", tags.join(", "))
+        };
+
         let start_time = Self::format_system_time(self.start_system_time);
         let end_time = self
             .end_system_time
@@ -101,159 +254,2159 @@ impl BenchmarkResult {
         let error_details = self
             .error
             .as_ref()
-            .map(|err| format!("Error Details: {}\n", err))
-            .unwrap_or_else(|| "".to_string());
+            .map(|err| {
+                let kind = self.error_kind.unwrap_or(ErrorKind::Other);
+                format!("Error Kind: {}\nError Details: {}\n", kind, err)
+            })
+            .unwrap_or_default();
+
+        let confirmation = self
+            .confirmation
+            .as_ref()
+            .map(Confirmation::display)
+            .unwrap_or_default();
+
+        let achieved_commitment = self
+            .achieved_commitment
+            .map(|c| format!("Achieved Commitment: {:?}\n", c))
+            .unwrap_or_default();
+
+        let journey = self
+            .journey
+            .as_ref()
+            .map(journey::Journey::display)
+            .unwrap_or_default();
+
+        let normalized = self
+            .normalized_duration_ms
+            .map(|ms| format!("Normalized Duration: {:.2}ms\n", ms))
+            .unwrap_or_default();
+
+        let extras: String = self
+            .extras
+            .iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(text) => format!("{}: {}\n", key, text),
+                other => format!("{}: {}\n", key, other),
+            })
+            .collect();
 
         format!(
-            "Endpoint: {}\nStart Time: {}\nEnd Time: {}\nStatus: {}\nTransaction Signature: {}\nTransaction Block Height: {}\n{}Duration: {}\n",
+            "Endpoint: {}\n{}{}{}{}Start Time: {}\nEnd Time: {}\nStatus: {}\nTransaction Signature: {}\nTransaction Block Height: {}\n{}{}{}{}{}Duration: {}\n{}",
             self.endpoint,
+            cluster,
+            http_version,
+            server_date,
+            tags,
             start_time,
             end_time,
             status,
             tx_signature,
             tx_block_height,
+            confirmation,
+            achieved_commitment,
+            journey,
+            extras,
             error_details,
-            duration
+            duration,
+            normalized
         )
     }
 }
 
-#[derive(Parser, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum OutputFormat {
+    /// Every result in full
+    Text,
+    /// Regions x metrics matrix derived from the per-endpoint summaries
+    RegionMatrix,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Combine result files from several runs into one with a unified summary
+    Merge(merge::MergeArgs),
+    /// Diff the per-endpoint latency of two --summary-json files
+    Compare(compare::CompareArgs),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum DumpFormat {
+    Json,
+    Yaml,
+}
+
+// Serializes as the effective configuration for --dump-config; anything
+// that may hold credentials is masked.
+#[derive(Parser, Debug, Serialize)]
 #[command(author, version, about, long_about = None)]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    #[serde(skip)]
+    command: Option<Command>,
+
     /// Comma-separated list of Solana RPC endpoints
+    #[arg(short, long, required_unless_present = "use_solana_config")]
+    #[serde(serialize_with = "json::redacted_endpoints")]
+    endpoints: Option<String>,
+
+    /// Default --endpoints and --keypair to the RPC URL and keypair in the
+    /// Solana CLI config (~/.config/solana/cli/config.yml)
+    #[arg(long)]
+    use_solana_config: bool,
+
+    /// Print the effective configuration, after the Solana CLI config is
+    /// applied, with credentials in endpoint URLs masked
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "json")]
+    #[serde(skip)]
+    dump_config: Option<DumpFormat>,
+
+    /// Exit right after --dump-config (JSON unless a format is given)
+    #[arg(long)]
+    #[serde(skip)]
+    dump_config_exit: bool,
+
+    /// Canonicalize endpoint URLs (lowercase host, no default port or
+    /// trailing slash) before removing duplicates
+    #[arg(long)]
+    normalize_urls: bool,
+
+    /// Print extra diagnostic output
     #[arg(short, long)]
-    endpoints: String,
+    verbose: bool,
 
-    /// Path to the Solana keypair JSON file
+    /// Path to the Solana keypair JSON file; only needed to sign transfers
+    /// or as the default --account
     #[arg(short = 'k', long = "keypair")]
-    keypair_path: PathBuf,
+    keypair_path: Option<PathBuf>,
+
+    /// Check every endpoint with this probe before benchmarking, falling
+    /// back to the others, and skip endpoints none of them reach
+    #[arg(long, value_enum)]
+    health_method: Option<health::HealthProbe>,
+
+    /// Call every read method once against each endpoint and print a
+    /// method x endpoint matrix of latencies, instead of benchmarking
+    #[arg(
+        long,
+        conflicts_with_all = ["detect_backends", "tps_ramp", "interval_secs", "presigned", "batch", "stream_to"]
+    )]
+    fuzz_methods: bool,
+
+    /// Probe each endpoint this many times on fresh connections and report
+    /// how many load-balancer backends answered, instead of benchmarking
+    #[arg(long, value_parser = clap::value_parser!(u64).range(2..))]
+    detect_backends: Option<u64>,
+
+    /// Comma-separated list of concurrency levels to benchmark the method at,
+    /// each running --iterations per worker (e.g. "1,2,4,8,16")
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["tps_ramp", "interval_secs", "presigned", "stream_to", "control_endpoint", "fuzz_methods", "detect_backends"]
+    )]
+    parallelism_sweep: Option<Vec<u64>>,
+
+    /// Comma-separated list of target TPS levels to step through (e.g. "100,200,400,800")
+    #[arg(long, value_delimiter = ',')]
+    tps_ramp: Option<Vec<u64>>,
+
+    /// Seconds to hold each TPS ramp level
+    #[arg(long, default_value_t = 10)]
+    ramp_step_secs: u64,
+
+    /// RPC method to benchmark; everything but transfer is read-only
+    #[arg(long, value_enum, default_value_t = Method::Transfer)]
+    method: Method,
+
+    /// Comma-separated read methods to try in order each iteration until one
+    /// succeeds (e.g. "version,health,block-height"); only the method that
+    /// answered is timed
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        conflicts_with_all = ["method", "batch", "bench_get_block_height_only", "expect_non_empty", "expect_min_slot", "presigned", "tps_ramp", "fuzz_methods"]
+    )]
+    method_fallback: Option<Vec<Method>>,
+
+    /// Lightest possible probe: a single getBlockHeight per iteration, with
+    /// no version check, payer balance check or extra instrumentation
+    #[arg(
+        long,
+        conflicts_with_all = ["method", "min_context_slot", "presigned", "tps_ramp"]
+    )]
+    bench_get_block_height_only: bool,
+
+    /// Account queried by get-balance and get-account-info (defaults to the
+    /// keypair's address)
+    #[arg(long)]
+    #[serde(serialize_with = "json::optional_display")]
+    account: Option<Pubkey>,
+
+    /// Require read methods to be served from at least this slot
+    #[arg(long)]
+    min_context_slot: Option<u64>,
+
+    /// After each confirmed transfer, read the payer's balance with the
+    /// transfer's slot as minContextSlot and time how long until the
+    /// endpoint serves it (read-after-write consistency)
+    #[arg(
+        long,
+        conflicts_with_all = ["min_context_slot", "presigned", "bench_get_block_height_only"]
+    )]
+    min_context_slot_auto: bool,
+
+    /// Switch to read-only benchmarking instead of failing every send when
+    /// the keypair cannot pay transaction fees
+    #[arg(long)]
+    auto_readonly_fallback: bool,
+
+    /// Seed for everything randomized in the run, such as --random-recipients
+    /// addresses; a random seed is used (and reported) when unset
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Send transfers to this many freshly generated recipient addresses,
+    /// cycling through them, instead of back to the payer
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    random_recipients: Option<u64>,
+
+    /// Create every recipient account before the benchmark starts (untimed),
+    /// so measured transfers never pay for account creation
+    #[arg(long, requires = "random_recipients")]
+    prime_accounts: bool,
+
+    /// Run continuously, benchmarking every endpoint once per cycle and
+    /// starting a new cycle every this many seconds
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "iterations",
+            "control_endpoint",
+            "stream_to",
+            "json_output",
+            "measure_block_height_drift"
+        ]
+    )]
+    interval_secs: Option<u64>,
+
+    /// Number of cycles to run in interval mode (0 runs until interrupted)
+    #[arg(long, default_value_t = 0, requires = "interval_secs")]
+    cycles: u32,
+
+    /// In interval mode, stop benchmarking an endpoint after this many
+    /// consecutive failed cycles
+    #[arg(long, requires = "interval_secs", value_parser = clap::value_parser!(u32).range(1..))]
+    breaker_threshold: Option<u32>,
+
+    /// In interval mode, draw a sparkline per endpoint of its p50 latency
+    /// over this many recent cycles
+    #[arg(long, requires = "interval_secs", value_parser = clap::value_parser!(u64).range(1..))]
+    sparkline: Option<u64>,
+
+    /// Number of cycles each sparkline point's p50 is computed over
+    #[arg(long, default_value_t = 5, requires = "sparkline", value_parser = clap::value_parser!(u64).range(1..))]
+    sparkline_window: u64,
+
+    /// Number of cycles a tripped endpoint is skipped before it is retried
+    #[arg(long, default_value_t = 5, requires = "breaker_threshold")]
+    breaker_cooldown: u32,
+
+    /// Submit the presigned transactions in this file (one base64-encoded
+    /// transaction per line) instead of building and signing transfers.
+    /// They are dealt out round-robin across endpoints
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "iterations",
+            "method",
+            "tps_ramp",
+            "random_recipients",
+            "interval_secs",
+            "control_endpoint"
+        ]
+    )]
+    presigned: Option<PathBuf>,
+
+    /// Once the run is over, wait this many seconds and then check how many
+    /// of the transactions each endpoint accepted actually finalized. Needs
+    /// --confirm-via, which records a signature even when confirmation fails
+    #[arg(long, requires = "confirm_via", conflicts_with_all = ["stream_to", "interval_secs"])]
+    verify_landing_after: Option<u64>,
+
+    /// Seconds before an RPC request times out
+    #[arg(long, default_value_t = 30)]
+    request_timeout_secs: u64,
+
+    /// HTTP version the benchmark clients speak: 1.1, 2 (without
+    /// negotiation) or auto (negotiated per connection)
+    #[arg(long, value_enum, default_value_t = protocol::HttpVersion::Auto)]
+    http_version: protocol::HttpVersion,
+
+    /// Write the moment each request was issued, as offsets from the run
+    /// start, to this file for --replay-schedule
+    #[arg(long, conflicts_with_all = ["stream_to", "interval_secs", "parallelism_sweep", "presigned"])]
+    record_schedule: Option<PathBuf>,
+
+    /// Issue requests at the offsets recorded with --record-schedule, one
+    /// recorded endpoint per endpoint, instead of back to back
+    #[arg(
+        long,
+        conflicts_with_all = ["sample_interval_ms", "iterations", "control_endpoint", "interval_secs", "parallelism_sweep", "presigned", "tps_ramp"]
+    )]
+    replay_schedule: Option<PathBuf>,
+
+    /// Pause this long between consecutive iterations against the same
+    /// endpoint; the pause is not part of any measured duration
+    #[arg(long, default_value_t = 0)]
+    sample_interval_ms: u64,
+
+    /// Retry an iteration up to this many times when it fails with a
+    /// timeout, rate limit or connection error
+    #[arg(long, default_value_t = 0)]
+    retries: u32,
+
+    /// Multiply the request timeout by this factor after each attempt that
+    /// timed out (e.g. 2 doubles it). Batches keep their client's timeout.
+    #[arg(
+        long,
+        default_value_t = 1.0,
+        requires = "retries",
+        conflicts_with = "batch",
+        value_parser = retry::parse_multiplier
+    )]
+    retry_timeout_multiplier: f64,
+
+    /// Number of benchmark iterations to run against each endpoint
+    #[arg(long, default_value_t = 1)]
+    iterations: u32,
+
+    /// Run every endpoint's iterations on a pool of this many threads, taking
+    /// turns across endpoints, instead of one thread per endpoint
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["control_endpoint", "presigned", "replay_schedule", "sample_interval_ms", "interval_secs", "parallelism_sweep"]
+    )]
+    workers: Option<u64>,
+
+    /// Record the block height every iteration and report the rate at which
+    /// it advances as observed through each endpoint
+    #[arg(long = "measure-getblockheight-drift")]
+    measure_block_height_drift: bool,
+
+    /// Control endpoint (typically a local validator) benchmarked in lockstep
+    /// with the others; its latency is subtracted to normalize their results
+    #[arg(long)]
+    #[serde(serialize_with = "json::redacted_endpoint")]
+    control_endpoint: Option<String>,
+
+    /// Confirm transactions by polling over HTTP, through a websocket
+    /// signature subscription, or both (default: the client's send-and-confirm)
+    #[arg(long, value_enum)]
+    confirm_via: Option<ConfirmVia>,
+
+    /// Milliseconds between HTTP confirmation polls
+    #[arg(long, default_value_t = 500)]
+    poll_interval_ms: u64,
+
+    /// Poll for HTTP confirmation every 50ms at first, doubling the delay up
+    /// to --poll-interval-ms, instead of at a fixed interval
+    #[arg(long, requires = "confirm_via")]
+    poll_adaptive: bool,
+
+    /// Seconds to wait for a transaction to be confirmed
+    #[arg(long, default_value_t = 60)]
+    confirm_timeout_secs: u64,
+
+    /// Only count a transfer as successful once its signature reaches this
+    /// commitment, re-polling until --confirm-timeout-secs passes
+    #[arg(long, value_enum)]
+    require_commitment: Option<Commitment>,
+
+    /// Re-fetch the blockhash before sending if fewer than this many blocks
+    /// of validity remain
+    #[arg(long)]
+    min_blockhash_validity: Option<u64>,
+
+    /// Comma-separated list of region tags, one per endpoint in --endpoints order
+    #[arg(long, value_delimiter = ',')]
+    regions: Option<Vec<String>>,
+
+    /// Console output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Format used for every timestamp in console and JSON output
+    #[arg(long, value_enum, default_value_t = TimeFormat::Local)]
+    time_format: TimeFormat,
+
+    /// Name of the benchmark profile (e.g. "reads"), shown in the report and
+    /// recorded in every JSON output
+    #[arg(long)]
+    benchmark_name: Option<String>,
+
+    /// Label every result with key=value; repeatable (e.g. --tag env=prod
+    /// --tag team=payments)
+    #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+    tags: Vec<(String, String)>,
+
+    /// Write all results as JSON to this file
+    #[arg(long)]
+    json_output: Option<PathBuf>,
+
+    /// Stream each result to this file as JSON Lines as soon as it completes
+    /// instead of keeping all results in memory
+    #[arg(
+        long,
+        conflicts_with_all = ["json_output", "control_endpoint", "measure_block_height_drift"]
+    )]
+    stream_to: Option<PathBuf>,
+
+    /// With --method journey, print how much of the end-to-end latency each
+    /// phase took per endpoint
+    #[arg(long)]
+    latency_budget: bool,
+
+    /// Count a read as failed unless it returned a non-empty value (not
+    /// null, false, zero or "")
+    #[arg(long, conflicts_with = "batch")]
+    expect_non_empty: bool,
+
+    /// Count a read as failed unless it was served at this slot or later
+    #[arg(long, conflicts_with = "batch")]
+    expect_min_slot: Option<u64>,
+
+    /// With a read method, send this many identical requests as one JSON-RPC
+    /// batch per iteration and report the per-request effective latency
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with = "bench_get_block_height_only"
+    )]
+    batch: Option<u64>,
+
+    /// Exit nonzero if any endpoint's latest block height is more than this
+    /// many blocks behind the highest seen across all endpoints
+    #[arg(long)]
+    max_block_height_age: Option<u64>,
+
+    /// Write only the per-endpoint aggregates as compact JSON to this file
+    #[arg(long)]
+    summary_json: Option<PathBuf>,
+
+    /// Comma-separated latency thresholds in milliseconds; reports the share
+    /// of iterations that succeeded within each (e.g. "100,250,500")
+    #[arg(long, value_delimiter = ',')]
+    slo_buckets: Option<Vec<u64>>,
+
+    /// List the N slowest individual iterations across the whole run
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    top_slow: Option<u64>,
+
+    /// Nest every iteration's duration under each endpoint in --summary-json
+    #[arg(long, requires = "summary_json")]
+    include_samples: bool,
+
+    /// Print nothing about successful results: only failures and a one-line
+    /// "N/M healthy" summary, for cron jobs that should stay silent
+    #[arg(
+        long,
+        conflicts_with_all = ["interval_secs", "stream_to", "parallelism_sweep", "tps_ramp", "fuzz_methods", "detect_backends"]
+    )]
+    quiet_success: bool,
 }
 
-fn main() {
-    let args = Args::parse();
+// Tag keys follow the Prometheus label name rules, which are the strictest
+// of the metric backends; names starting with "__" are reserved.
+fn parse_tag(tag: &str) -> Result<(String, String), String> {
+    let (key, value) = tag
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got {:?}", tag))?;
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !key.starts_with("__");
+    if !valid {
+        return Err(format!(
+            "invalid tag key {:?}: use letters, digits and underscores, not starting with a digit or \"__\"",
+            key
+        ));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
 
-    let keypair = read_keypair_file(&args.keypair_path).unwrap();
-    println!("Using Solana keypair at: {}", args.keypair_path.display());
-    println!("Keypair public address: {}", keypair.pubkey());
+// Base fee for a single-signature transaction
+const MIN_FUNDED_LAMPORTS: u64 = 5_000;
 
-    let endpoints: Vec<String> = args
-        .endpoints
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .collect();
+// Options shared by every endpoint thread
+#[derive(Debug)]
+struct BenchmarkConfig {
+    method: Method,
+    // Set by --bench-get-block-height-only
+    minimal: bool,
+    account: Pubkey,
+    min_context_slot: Option<u64>,
+    min_context_slot_auto: bool,
+    iterations: u32,
+    sample_interval: Duration,
+    confirm_via: Option<ConfirmVia>,
+    poll_interval: Duration,
+    poll_adaptive: bool,
+    confirm_timeout: Duration,
+    min_blockhash_validity: Option<u64>,
+    require_commitment: Option<Commitment>,
+    recipients: Option<recipients::Recipients>,
+    presigned: Vec<Transaction>,
+    batch: Option<batch::Batch>,
+    expect: methods::Expectations,
+    // Detected cluster per endpoint URL
+    clusters: HashMap<String, &'static str>,
+    // Health probe each endpoint passed, with --health-method
+    health_probes: HashMap<String, health::HealthProbe>,
+    tags: BTreeMap<String, String>,
+    benchmark_name: Option<String>,
+    retry: retry::RetryPolicy,
+    // Run start, the origin of recorded and replayed schedule offsets
+    started: Instant,
+    replay: Option<schedule::Schedule>,
+    // Set by --method-fallback; empty otherwise
+    fallback: Vec<Method>,
+    // Set by --quiet-success
+    quiet: bool,
+}
 
-    println!(
-        "\nStarting benchmark for {} endpoints...\n",
-        endpoints.len()
-    );
+impl BenchmarkConfig {
+    // Why the method can't run against this node, if it can't. Minimal
+    // probes skip the getVersion call this takes.
+    fn unsupported_on(&self, rpc_client: &RpcClient) -> Option<String> {
+        // A fallback chain finds out method by method instead
+        if self.minimal || !self.fallback.is_empty() {
+            return None;
+        }
+        self.method.unsupported_on(rpc_client)
+    }
 
-    let mut handles = vec![];
+    // Per-iteration progress, which --quiet-success keeps off the console
+    fn progress(&self, message: impl std::fmt::Display) {
+        if !self.quiet {
+            println!("{}", message);
+        }
+    }
 
-    // Spawn a thread for each endpoint
-    let keypair = Arc::new(keypair);
-    for endpoint in endpoints {
-        let keypair = Arc::clone(&keypair);
-        let handle = thread::spawn(move || {
-            let mut result = BenchmarkResult::new(endpoint.clone());
+    // Attaches the run-wide labels to a finished result
+    fn label(&self, result: &mut BenchmarkResult) {
+        result.cluster = self.clusters.get(&result.endpoint).copied();
+        result.tags = self.tags.clone();
+        result.benchmark = self.benchmark_name.clone();
+        if let Some(probe) = self.health_probes.get(&result.endpoint) {
+            result.set_extra("health_probe", probe.as_str());
+        }
+    }
+}
+
+fn run_iteration(
+    endpoint: &str,
+    rpc_client: &RpcClient,
+    keypair: Option<&Keypair>,
+    config: &BenchmarkConfig,
+    iteration: u32,
+    lamports: u64,
+) -> BenchmarkResult {
+    let mut result = BenchmarkResult::new(endpoint.to_string(), iteration);
+
+    if config.minimal {
+        match rpc_client.get_block_height() {
+            Ok(height) => result.set_block_height(height),
+            Err(err) => result.set_error(ErrorKind::of(&err), err.to_string()),
+        }
+        result.complete();
+        return result;
+    }
+
+    if let Some(ref batch) = config.batch {
+        batch.run(
+            endpoint,
+            config.method,
+            &config.account,
+            rpc_client.commitment(),
+            config.min_context_slot,
+            &mut result,
+        );
+        result.complete();
+        if let Some(duration_ms) = result.duration_ms {
+            result.set_extra("per_request_ms", duration_ms / batch.size as f64);
+        }
+        return result;
+    }
+
+    if !config.fallback.is_empty() {
+        return methods::run_fallback(
+            &config.fallback,
+            rpc_client,
+            &config.account,
+            config.min_context_slot,
+            result,
+        );
+    }
+
+    if config.method.is_read_only() {
+        methods::run_read(
+            config.method,
+            rpc_client,
+            &config.account,
+            config.min_context_slot,
+            &mut result,
+        );
+        config.expect.check(config.method, &mut result);
+        result.complete();
+        return result;
+    }
+
+    let keypair = keypair.expect("transfers always load the keypair");
 
-            // Create RPC client and fetch block height
-            let rpc_client = RpcClient::new(endpoint.clone());
+    match rpc_client.get_block_height() {
+        Ok(height) => {
+            result.set_block_height(height);
+        }
+        Err(err) => {
+            result.set_error(ErrorKind::of(&err), err.to_string());
+            result.complete();
+            return result;
+        }
+    }
 
-            println!("Connecting to {}", endpoint);
+    // Create a simple transfer instruction
+    let (recipient, lamports) = match config.recipients {
+        Some(ref recipients) => recipients.transfer(lamports),
+        None => (keypair.pubkey(), lamports),
+    };
+    let instruction = system_instruction::transfer(&keypair.pubkey(), &recipient, lamports);
 
-            match rpc_client.get_block_height() {
-                Ok(height) => {
-                    result.set_block_height(height);
+    if config.method == Method::Journey {
+        journey::run(rpc_client, keypair, instruction, config, &mut result);
+        result.complete();
+        return result;
+    }
+
+    // Create and sign transaction - try multiple methods to get a blockhash
+    let (mut recent_blockhash, last_valid_block_height) = {
+        // Method 1: Try get_latest_blockhash_with_commitment, which also
+        // reports how long the blockhash stays valid
+        if let Ok((blockhash, last_valid_block_height)) =
+            rpc_client.get_latest_blockhash_with_commitment(rpc_client.commitment())
+        {
+            config.progress("Got blockhash using get_latest_blockhash_with_commitment");
+            result.set_extra("blockhash_method", "get_latest_blockhash_with_commitment");
+            (blockhash, Some(last_valid_block_height))
+        }
+        // Method 2: Try get_latest_blockhash
+        else {
+            match rpc_client.get_latest_blockhash() {
+                Ok(blockhash) => {
+                    config.progress("Got blockhash using get_latest_blockhash");
+                    result.set_extra("blockhash_method", "get_latest_blockhash");
+                    (blockhash, None)
                 }
+                // All methods failed
                 Err(err) => {
-                    result.set_error(err.to_string());
+                    result.set_error(
+                        ErrorKind::of(&err),
+                        "Failed to get blockhash: All available methods failed".to_string(),
+                    );
                     result.complete();
                     return result;
                 }
             }
+        }
+    };
+
+    // On high-latency links a freshly fetched blockhash can be close to
+    // expiry by the time it is sent, which surfaces as BlockhashNotFound.
+    // Re-fetch it up front if too little validity is left.
+    if let (Some(min_validity), Some(last_valid_block_height)) =
+        (config.min_blockhash_validity, last_valid_block_height)
+        && let Ok(current_height) = rpc_client.get_block_height()
+    {
+        let remaining = last_valid_block_height.saturating_sub(current_height);
+        result.set_extra("blockhash_remaining_blocks", remaining);
+        if remaining < min_validity {
+            config.progress(format!(
+                "Blockhash has only {} blocks of validity left, re-fetching",
+                remaining
+            ));
+            let refetched =
+                rpc_client.get_latest_blockhash_with_commitment(rpc_client.commitment());
+            if let Ok((blockhash, _)) = refetched {
+                recent_blockhash = blockhash;
+            }
+            result.set_extra("blockhash_refetched", refetched.is_ok());
+        }
+    }
+
+    config.progress(format!("Blockhash: {}", recent_blockhash));
+    result.set_extra("blockhash", recent_blockhash.to_string());
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&keypair.pubkey()),
+        &[keypair],
+        recent_blockhash,
+    );
+
+    submit_transaction(endpoint, rpc_client, &transaction, config, &mut result);
+    result.complete();
+
+    if config.min_context_slot_auto
+        && result.error.is_none()
+        && let Some(signature) = result.transaction_signature
+    {
+        freshness::read_after_write(
+            rpc_client,
+            &keypair.pubkey(),
+            &signature,
+            config.confirm_timeout,
+            &mut result,
+        );
+    }
+    result
+}
 
-            // Create a simple transfer instruction
-            let instruction = system_instruction::transfer(
-                &keypair.pubkey(),
-                &keypair.pubkey(),
-                1, // Send 1 lamport to self
+// Sends an already signed transaction and waits for it to be confirmed,
+// recording the outcome on `result`.
+fn submit_transaction(
+    endpoint: &str,
+    rpc_client: &RpcClient,
+    transaction: &Transaction,
+    config: &BenchmarkConfig,
+    result: &mut BenchmarkResult,
+) {
+    let sent = match config.confirm_via {
+        Some(via) => {
+            let poll_schedule = if config.poll_adaptive {
+                PollSchedule::Adaptive {
+                    max: config.poll_interval,
+                }
+            } else {
+                PollSchedule::Fixed(config.poll_interval)
+            };
+            let outcome = confirm::send_and_confirm(
+                endpoint,
+                rpc_client,
+                transaction,
+                via,
+                poll_schedule,
+                config.confirm_timeout,
             );
+            result.confirmation = Some(outcome.confirmation);
+            match (outcome.signature, outcome.error) {
+                (Some(signature), None) => Ok(signature),
+                (signature, error) => {
+                    if let Some(signature) = signature {
+                        result.set_transaction_signature(signature);
+                    }
+                    Err(error.unwrap_or((ErrorKind::Other, String::new())))
+                }
+            }
+        }
+        None => rpc_client
+            .send_and_confirm_transaction(transaction)
+            .map_err(|err| (ErrorKind::of(&err), format!("Transaction failed: {}", err))),
+    };
+
+    match sent {
+        Ok(signature) => {
+            config.progress(format!("Transaction signature: {}", signature));
 
-            // Create and sign transaction - try multiple methods to get a blockhash
-            let recent_blockhash = {
-                // Method 1: Try get_latest_blockhash (newer method)
-                if let Ok(blockhash) = rpc_client.get_latest_blockhash() {
-                    println!("Got blockhash using get_latest_blockhash");
-                    blockhash
+            result.set_transaction_signature(signature);
+
+            if let Some(required) = config.require_commitment {
+                let achieved = confirm::wait_for_commitment(
+                    rpc_client,
+                    &signature,
+                    required,
+                    config.poll_interval,
+                    config.confirm_timeout,
+                );
+                result.achieved_commitment = achieved;
+                if achieved < Some(required) {
+                    result.set_error(
+                        ErrorKind::Timeout,
+                        format!(
+                            "Transaction did not reach {:?} commitment within {:?}",
+                            required, config.confirm_timeout
+                        ),
+                    );
+                    return;
                 }
-                // Method 2: Try get_latest_blockhash_with_commitment
-                else if let Ok((blockhash, _)) =
-                    rpc_client.get_latest_blockhash_with_commitment(rpc_client.commitment())
-                {
-                    println!("Got blockhash using get_latest_blockhash_with_commitment");
-                    blockhash
+            }
+
+            // Get the block height for the confirmed transaction
+            match rpc_client.get_slot_with_commitment(rpc_client.commitment()) {
+                Ok(slot) => {
+                    result.set_transaction_block_height(slot);
                 }
-                // All methods failed
-                else {
+                Err(err) => {
                     result.set_error(
-                        "Failed to get blockhash: All available methods failed".to_string(),
+                        ErrorKind::of(&err),
+                        format!("Failed to get transaction block height: {}", err),
                     );
-                    result.complete();
-                    return result;
                 }
+            }
+        }
+        Err((kind, err)) => {
+            result.set_error(kind, err);
+        }
+    }
+}
+
+// Records an iteration that was skipped because the node can't serve the method
+fn unsupported_iteration(endpoint: &str, iteration: u32, reason: &str) -> BenchmarkResult {
+    let mut result = BenchmarkResult::new(endpoint.to_string(), iteration);
+    result.set_error(ErrorKind::Unsupported, reason.to_string());
+    result.complete();
+    result
+}
+
+// Checks the payer's balance through the first endpoint that answers
+fn payer_balance(endpoints: &[String], payer: &Pubkey) -> Option<u64> {
+    endpoints
+        .iter()
+        .find_map(|endpoint| RpcClient::new(endpoint.clone()).get_balance(payer).ok())
+}
+
+fn spawn_endpoint(
+    endpoint: String,
+    endpoint_index: usize,
+    endpoint_count: u64,
+    keypair: Option<Arc<Keypair>>,
+    config: Arc<BenchmarkConfig>,
+    barrier: Option<Arc<Barrier>>,
+    results: Sender<(usize, BenchmarkResult)>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        // Create the RPC client once and reuse it across iterations
+        let rpc_client = config.retry.client(&endpoint);
+
+        config.progress(format!("Connecting to {}", endpoint));
+        let unsupported = config.unsupported_on(&rpc_client);
+
+        if !config.presigned.is_empty() {
+            let share = config
+                .presigned
+                .iter()
+                .skip(endpoint_index)
+                .step_by(endpoint_count as usize);
+            for (iteration, transaction) in share.enumerate() {
+                if iteration > 0 {
+                    thread::sleep(config.sample_interval);
+                }
+                let submitted = presigned::submit(
+                    &endpoint,
+                    &rpc_client,
+                    transaction,
+                    &config,
+                    iteration as u32,
+                );
+                if let Some(mut result) = submitted {
+                    config.label(&mut result);
+                    let _ = results.send((endpoint_index, result));
+                }
+            }
+            return;
+        }
+
+        let iterations = match config.replay {
+            Some(ref replay) => replay.iterations(endpoint_index),
+            None => config.iterations,
+        };
+        for iteration in 0..iterations {
+            // Spacing samples out keeps them independent and under rate
+            // limits; it happens before the barrier so threads stay in step.
+            if let Some(ref replay) = config.replay {
+                replay.wait(config.started, endpoint_index, iteration);
+            } else if iteration > 0 {
+                thread::sleep(config.sample_interval);
+            }
+            if let Some(ref barrier) = barrier {
+                barrier.wait();
+            }
+            // All endpoints send self-transfers from the same keypair,
+            // so vary the amount to keep every signature unique.
+            let lamports = iteration as u64 * endpoint_count + endpoint_index as u64 + 1;
+            let mut result = match unsupported {
+                Some(ref reason) => unsupported_iteration(&endpoint, iteration, reason),
+                None => config.retry.run(&endpoint, &rpc_client, |rpc_client| {
+                    run_iteration(
+                        &endpoint,
+                        rpc_client,
+                        keypair.as_deref(),
+                        &config,
+                        iteration,
+                        lamports,
+                    )
+                }),
             };
+            config.label(&mut result);
+            // Hand each result over as soon as it completes
+            let _ = results.send((endpoint_index, result));
+        }
+    })
+}
+
+fn summarize(
+    aliases: &[String],
+    endpoints: &[String],
+    region_tags: &[String],
+    options: &summary::SummaryOptions,
+    mut endpoint_samples: impl FnMut(usize, &str) -> Vec<summary::Sample>,
+) -> BTreeMap<String, summary::EndpointSummary> {
+    aliases
+        .iter()
+        .zip(endpoints)
+        .enumerate()
+        .map(|(index, (alias, endpoint))| {
+            let samples = endpoint_samples(index, endpoint);
+            let mut summary = summary::EndpointSummary::from_samples(endpoint, &samples)
+                .with_options(&samples, options);
+            summary.region = region_tags.get(index).cloned();
+            (alias.clone(), summary)
+        })
+        .collect()
+}
+
+// Prints the per-endpoint summaries and writes them to --summary-json, for the
+// modes that don't print every result.
+fn report_summaries(
+    format: OutputFormat,
+    metadata: &metadata::RunMetadata,
+    summaries: BTreeMap<String, summary::EndpointSummary>,
+    summary_json: Option<PathBuf>,
+) {
+    print_benchmark_name(metadata);
+    match format {
+        OutputFormat::Text => {
+            println!("\nBenchmark Summary:");
+            println!("==================\n");
+            print!("{}", summary::display_table(&summaries));
+        }
+        OutputFormat::RegionMatrix => {
+            println!("\nRegion Matrix:");
+            println!("==============\n");
+            print!("{}", regions::display(&regions::region_matrix(&summaries)));
+        }
+    }
+    print_slo_buckets(&summaries);
+
+    if let Some(path) = summary_json {
+        let report = summary::SummaryReport {
+            metadata,
+            endpoints: summaries,
+        };
+        write_json_or_exit(&path, &report);
+        println!("\nJSON summary written to {}", path.display());
+    }
+}
+
+// The freshness gate: whether every endpoint's latest block height is within
+// `max_age` of the highest any endpoint reported, with a report naming the
+// endpoints that breached. Endpoints that reported no height breach too,
+// since their freshness can't be vouched for.
+fn block_height_gate(
+    summaries: &BTreeMap<String, summary::EndpointSummary>,
+    max_age: u64,
+) -> (bool, String) {
+    let highest = summaries
+        .values()
+        .filter_map(|summary| summary.latest_block_height)
+        .max();
+    let mut behind = vec![];
+    let mut unreported = vec![];
+    for (alias, summary) in summaries {
+        match (summary.latest_block_height, highest) {
+            (Some(height), Some(highest)) if highest - height > max_age => behind.push(format!(
+                "  {}: block height {} is {} behind {}\n",
+                alias,
+                height,
+                highest - height,
+                highest
+            )),
+            (Some(_), _) => {}
+            (None, _) => unreported.push(format!("  {}\n", alias)),
+        }
+    }
+
+    if behind.is_empty() && unreported.is_empty() {
+        let report = format!(
+            "PASSED: every endpoint is within {} blocks of the highest\n",
+            max_age
+        );
+        return (true, report);
+    }
+    let mut report = String::new();
+    if !behind.is_empty() {
+        report.push_str(&format!(
+            "FAILED: {} of {} endpoints are more than {} blocks behind\n",
+            behind.len(),
+            summaries.len(),
+            max_age
+        ));
+        report.extend(behind);
+    }
+    if !unreported.is_empty() {
+        report.push_str(&format!(
+            "FAILED: {} of {} endpoints reported no block height\n",
+            unreported.len(),
+            summaries.len()
+        ));
+        report.extend(unreported);
+    }
+    (false, report)
+}
+
+fn print_block_height_gate(gate: &Option<(bool, String)>, quiet: bool) {
+    if let Some((fresh, report)) = gate
+        && !(quiet && *fresh)
+    {
+        println!("\nFreshness Gate:");
+        println!("===============\n");
+        print!("{}", report);
+    }
+}
 
-            println!("Blockhash: {}", recent_blockhash);
+fn print_slo_buckets(summaries: &BTreeMap<String, summary::EndpointSummary>) {
+    if summaries.values().all(|summary| summary.slo.is_empty()) {
+        return;
+    }
+    println!("\nLatency SLO:");
+    println!("============\n");
+    print!("{}", summary::display_slo_table(summaries));
+}
+
+fn print_benchmark_name(metadata: &metadata::RunMetadata) {
+    if let Some(ref name) = metadata.benchmark_name {
+        println!("\nBenchmark: {}", name);
+    }
+}
+
+// --quiet-success output: the failed results only, in the chosen format,
+// then how many endpoints had no failures at all
+fn print_failures(
+    format: OutputFormat,
+    results: &[BenchmarkResult],
+    endpoints: &[String],
+    summaries: &BTreeMap<String, summary::EndpointSummary>,
+    benchmark_name: Option<&str>,
+) {
+    let unhealthy: BTreeMap<String, summary::EndpointSummary> = summaries
+        .iter()
+        .filter(|(_, summary)| summary.successes < summary.samples || summary.samples == 0)
+        .map(|(alias, summary)| (alias.clone(), summary.clone()))
+        .collect();
 
-            let transaction = Transaction::new_signed_with_payer(
-                &[instruction],
-                Some(&keypair.pubkey()),
-                &[&keypair],
-                recent_blockhash,
+    if !unhealthy.is_empty() {
+        match format {
+            OutputFormat::Text => {
+                println!("Failed Results:");
+                println!("===============");
+                for result in results.iter().filter(|result| result.error.is_some()) {
+                    let number = endpoints
+                        .iter()
+                        .position(|endpoint| *endpoint == result.endpoint)
+                        .unwrap_or_default()
+                        + 1;
+                    println!(
+                        "\nEndpoint #{}, Iteration #{}",
+                        number,
+                        result.iteration + 1
+                    );
+                    println!("-----------");
+                    print!("{}", result.display());
+                }
+            }
+            OutputFormat::RegionMatrix => {
+                println!("Region Matrix:");
+                println!("==============\n");
+                print!("{}", regions::display(&regions::region_matrix(&unhealthy)));
+            }
+        }
+        println!();
+    }
+    let prefix = benchmark_name
+        .map(|name| format!("{}: ", name))
+        .unwrap_or_default();
+    println!(
+        "{}{}/{} healthy",
+        prefix,
+        summaries.len() - unhealthy.len(),
+        summaries.len()
+    );
+}
+
+// The slowest iterations across all endpoints, failed ones included, with
+// enough context to line each up against cluster events
+fn print_slowest(results: &[BenchmarkResult], endpoints: &[String], aliases: &[String], n: usize) {
+    let mut slowest: Vec<(&BenchmarkResult, f64)> = results
+        .iter()
+        .filter_map(|result| Some((result, result.duration_ms?)))
+        .collect();
+    slowest.sort_by(|a, b| b.1.total_cmp(&a.1));
+    slowest.truncate(n);
+
+    let alias = |result: &BenchmarkResult| {
+        endpoints
+            .iter()
+            .position(|endpoint| *endpoint == result.endpoint)
+            .map(|index| aliases[index].clone())
+            .unwrap_or_else(|| result.endpoint.clone())
+    };
+    let width = slowest
+        .iter()
+        .map(|(result, _)| alias(result).len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let title = format!("Top {} Slowest Requests:", slowest.len());
+    println!("\n{}", title);
+    println!("{}\n", "=".repeat(title.len()));
+    println!(
+        "{:>4}  {:<width$} {:>9} {:>12}  {:<12} Start Time",
+        "#", "Endpoint", "Iteration", "Duration", "Status"
+    );
+    for (rank, (result, duration_ms)) in slowest.iter().enumerate() {
+        let status = match result.error_kind {
+            Some(kind) => kind.to_string(),
+            None if result.error.is_some() => ErrorKind::Other.to_string(),
+            None => "ok".to_string(),
+        };
+        println!(
+            "{:>4}  {:<width$} {:>9} {:>12}  {:<12} {}",
+            rank + 1,
+            alias(result),
+            result.iteration + 1,
+            format!("{:.2}ms", duration_ms),
+            status,
+            BenchmarkResult::format_system_time(result.start_system_time)
+        );
+    }
+}
+
+// Fills --endpoints and --keypair from the Solana CLI config where they
+// weren't given explicitly
+fn apply_solana_config(args: &mut Args) {
+    let Some(path) = solana_config::default_path() else {
+        eprintln!("Cannot locate the Solana CLI config: HOME is not set");
+        std::process::exit(1);
+    };
+    let config = match solana_config::load(&path) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!(
+                "Failed to read Solana CLI config {}: {}",
+                path.display(),
+                err
+            );
+            std::process::exit(1);
+        }
+    };
+    if args.endpoints.is_none() {
+        let Some(url) = config.json_rpc_url else {
+            eprintln!("{} has no json_rpc_url; pass --endpoints", path.display());
+            std::process::exit(1);
+        };
+        // On stderr and masked, so a --dump-config on stdout stays parseable
+        // and doesn't leak the key the dump masks
+        eprintln!("Endpoint from {}: {}", path.display(), urls::redact(&url));
+        args.endpoints = Some(url);
+    }
+    if args.keypair_path.is_none()
+        && let Some(keypair_path) = config.keypair_path
+    {
+        eprintln!(
+            "Keypair from {}: {}",
+            path.display(),
+            keypair_path.display()
+        );
+        args.keypair_path = Some(keypair_path);
+    }
+}
+
+fn dump_config(args: &Args, format: DumpFormat) {
+    let dumped = match format {
+        DumpFormat::Json => serde_json::to_string_pretty(args).map_err(|err| err.to_string()),
+        DumpFormat::Yaml => serde_yaml::to_string(args).map_err(|err| err.to_string()),
+    };
+    match dumped {
+        Ok(dumped) => println!("{}", dumped.trim_end()),
+        Err(err) => {
+            eprintln!("Failed to serialize the configuration: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let mut args = Args::parse();
+    args.time_format.install();
+
+    if let Some(command) = args.command {
+        match command {
+            Command::Merge(merge_args) => merge::run(merge_args),
+            Command::Compare(compare_args) => compare::run(compare_args),
+        }
+        return;
+    }
+
+    if args.use_solana_config {
+        apply_solana_config(&mut args);
+    }
+
+    if let Some(format) = args
+        .dump_config
+        .or(args.dump_config_exit.then_some(DumpFormat::Json))
+    {
+        dump_config(&args, format);
+        if args.dump_config_exit {
+            return;
+        }
+    }
+
+    let fallback = args.method_fallback.clone().unwrap_or_default();
+    if fallback.iter().any(|method| !method.is_read_only()) {
+        eprintln!("--method-fallback only takes read methods");
+        std::process::exit(1);
+    }
+
+    let method = if args.bench_get_block_height_only {
+        Method::BlockHeight
+    } else if let Some(&first) = fallback.first() {
+        first
+    } else {
+        args.method
+    };
+
+    // The keypair is only read when something is signed with it, or when it
+    // stands in for a missing --account. The method survey uses it if given.
+    let signs = !args.fuzz_methods
+        && (args.tps_ramp.is_some() || (args.presigned.is_none() && !method.is_read_only()));
+    // The first method that needs an account, if any does
+    let account_reader = std::iter::once(method)
+        .chain(fallback.iter().copied())
+        .find(|method| method.reads_account());
+    let needs_account = (account_reader.is_some() || args.fuzz_methods) && args.account.is_none();
+    let keypair = match args.keypair_path {
+        _ if args.detect_backends.is_some() || !(signs || needs_account) => None,
+        Some(ref keypair_path) => {
+            let keypair = match read_keypair_file(keypair_path) {
+                Ok(keypair) => keypair,
+                Err(err) => {
+                    eprintln!("Failed to read keypair {}: {}", keypair_path.display(), err);
+                    std::process::exit(1);
+                }
+            };
+            if !args.quiet_success {
+                println!("Using Solana keypair at: {}", keypair_path.display());
+                println!("Keypair public address: {}", keypair.pubkey());
+            }
+            Some(Arc::new(keypair))
+        }
+        None if signs => {
+            eprintln!("--keypair is required to sign transactions");
+            std::process::exit(1);
+        }
+        None if args.fuzz_methods => None,
+        None => {
+            eprintln!(
+                "--method {} needs --account or --keypair",
+                account_reader.unwrap_or(method).name()
+            );
+            std::process::exit(1);
+        }
+    };
+
+    // Required by clap unless a subcommand or --use-solana-config was given,
+    // and the latter exits without a URL
+    let mut endpoints: Vec<String> = args
+        .endpoints
+        .as_deref()
+        .unwrap()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .collect();
+
+    if args.normalize_urls {
+        for endpoint in &mut endpoints {
+            let normalized = match urls::normalize(endpoint) {
+                Ok(normalized) => normalized,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            };
+            if args.verbose && normalized != *endpoint {
+                println!("Normalized endpoint {} to {}", endpoint, normalized);
+            }
+            *endpoint = normalized;
+        }
+    }
+
+    if args.min_context_slot_auto && args.method != Method::Transfer {
+        eprintln!("--min-context-slot-auto needs --method transfer");
+        std::process::exit(1);
+    }
+
+    if args.latency_budget && args.method != Method::Journey {
+        eprintln!("--latency-budget needs --method journey");
+        std::process::exit(1);
+    }
+
+    if args.batch.is_some() && !method.is_read_only() {
+        eprintln!("--batch needs a read method");
+        std::process::exit(1);
+    }
+    if (args.expect_non_empty || args.expect_min_slot.is_some()) && !method.is_read_only() {
+        eprintln!("--expect-non-empty and --expect-min-slot need a read method");
+        std::process::exit(1);
+    }
+    if args.max_block_height_age.is_some() {
+        // Every method of a fallback chain may end up answering
+        let unreported = std::iter::once(method)
+            .chain(fallback.iter().copied())
+            .find(|method| !method.reports_block_height());
+        if let Some(method) = unreported {
+            eprintln!(
+                "--method {} doesn't report a block height for --max-block-height-age",
+                method.name()
             );
+            std::process::exit(1);
+        }
+        if args.batch.is_some() || args.presigned.is_some() {
+            eprintln!(
+                "--max-block-height-age doesn't support --batch or --presigned, which record no block height"
+            );
+            std::process::exit(1);
+        }
+    }
+
+    if args.min_context_slot.is_some() {
+        let unsent = std::iter::once(method)
+            .chain(fallback.iter().copied())
+            .find(|method| !method.takes_min_context_slot());
+        if let Some(method) = unsent {
+            eprintln!(
+                "--method {} doesn't send a minContextSlot for --min-context-slot",
+                method.name()
+            );
+            std::process::exit(1);
+        }
+    }
+
+    if args.expect_min_slot.is_some() && !method.reports_slot() {
+        eprintln!(
+            "--method {} doesn't report a slot for --expect-min-slot",
+            method.name()
+        );
+        std::process::exit(1);
+    }
+
+    if args.batch.is_some() && method == Method::CommitmentLag {
+        eprintln!("--batch doesn't support --method commitment-lag, which reads two commitments");
+        std::process::exit(1);
+    }
+
+    if args.batch.is_some() && method == Method::FirstAvailableBlock {
+        eprintln!(
+            "--batch doesn't support --method first-available-block, which also reads the slot"
+        );
+        std::process::exit(1);
+    }
 
-            match rpc_client.send_and_confirm_transaction(&transaction) {
-                Ok(signature) => {
-                    println!("Transaction signature: {}", signature);
+    if let Some(ref regions) = args.regions
+        && regions.len() != endpoints.len()
+    {
+        eprintln!(
+            "--regions lists {} regions but {} endpoints were given",
+            regions.len(),
+            endpoints.len()
+        );
+        std::process::exit(1);
+    }
+
+    let mut region_tags = args.regions.clone();
+    for duplicate in urls::dedupe(&mut endpoints, region_tags.as_mut()) {
+        println!("Skipping duplicate endpoint {}", duplicate);
+    }
 
-                    result.set_transaction_signature(signature);
-                    // Get the block height for the confirmed transaction
-                    match rpc_client.get_slot_with_commitment(rpc_client.commitment()) {
-                        Ok(slot) => {
-                            result.set_transaction_block_height(slot);
+    let mut health_probes = HashMap::new();
+    if let Some(preferred) = args.health_method {
+        let timeout = Duration::from_secs(args.request_timeout_secs);
+        let reachable: Vec<bool> = endpoints
+            .iter()
+            .map(
+                |endpoint| match health::check(endpoint, preferred, timeout) {
+                    Ok(probe) => {
+                        if probe != preferred {
+                            println!(
+                                "{}: {} failed, but {} succeeded",
+                                endpoint, preferred, probe
+                            );
                         }
-                        Err(err) => {
-                            result.set_error(format!(
-                                "Failed to get transaction block height: {}",
-                                err
-                            ));
+                        health_probes.insert(endpoint.clone(), probe);
+                        true
+                    }
+                    Err(failures) => {
+                        println!("Skipping unreachable endpoint {}", endpoint);
+                        for failure in failures {
+                            println!("  {}", failure);
                         }
+                        false
                     }
-                }
+                },
+            )
+            .collect();
+        if let Some(ref mut regions) = region_tags {
+            let mut keep = reachable.iter();
+            regions.retain(|_| *keep.next().unwrap_or(&true));
+        }
+        let mut keep = reachable.iter();
+        endpoints.retain(|_| *keep.next().unwrap());
+        if endpoints.is_empty() {
+            eprintln!("No endpoint passed the health check");
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(probes) = args.detect_backends {
+        let aliases = summary::endpoint_aliases(&endpoints);
+        println!(
+            "\nProbing {} endpoints for load-balancer backends...",
+            endpoints.len()
+        );
+        let reports: BTreeMap<String, backends::BackendReport> = aliases
+            .into_iter()
+            .zip(&endpoints)
+            .map(|(alias, endpoint)| (alias, backends::detect(endpoint, probes as usize)))
+            .collect();
+        println!("\nBackends:");
+        println!("=========");
+        print!("{}", backends::display(&reports));
+        return;
+    }
+
+    if args.fuzz_methods {
+        let aliases = summary::endpoint_aliases(&endpoints);
+        println!(
+            "\nCalling every read method once against {} endpoints...",
+            endpoints.len()
+        );
+        let account = args
+            .account
+            .or_else(|| keypair.as_ref().map(|keypair| keypair.pubkey()));
+        let survey = fuzz::run(
+            &endpoints,
+            account,
+            Duration::from_secs(args.request_timeout_secs),
+        );
+        println!("\nMethod Support:");
+        println!("===============\n");
+        print!("{}", fuzz::display(&survey, &aliases));
+        return;
+    }
+
+    if let Some(levels) = args.tps_ramp {
+        ramp::run(
+            endpoints,
+            keypair.expect("ramps always load the keypair"),
+            &levels,
+            Duration::from_secs(args.ramp_step_secs),
+        );
+        return;
+    }
+
+    let mut method = method;
+    if method == Method::Transfer
+        && let Some(ref keypair) = keypair
+        && let Some(balance) = payer_balance(&endpoints, &keypair.pubkey())
+        && balance < MIN_FUNDED_LAMPORTS
+    {
+        println!(
+            "\nWarning: keypair {} holds {} lamports, not enough to pay for a transfer",
+            keypair.pubkey(),
+            balance
+        );
+        if args.auto_readonly_fallback {
+            println!("Falling back to read-only benchmarking (--method block-height)");
+            method = Method::BlockHeight;
+        }
+    }
+
+    // The one source of randomness for the run, so a seed reproduces it
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut recipients = None;
+    if let Some(count) = args.random_recipients
+        && method == Method::Transfer
+    {
+        let rpc_client = RpcClient::new(endpoints[0].clone());
+        let rent_exempt_minimum = match rpc_client.get_minimum_balance_for_rent_exemption(0) {
+            Ok(lamports) => lamports,
+            Err(err) => {
+                eprintln!("Failed to get the rent-exempt minimum: {}", err);
+                std::process::exit(1);
+            }
+        };
+        let mut generated =
+            recipients::Recipients::generate(count as usize, rent_exempt_minimum, &mut rng);
+        if args.prime_accounts {
+            let started = Instant::now();
+            let payer = keypair
+                .as_deref()
+                .expect("transfers always load the keypair");
+            match generated.prime(&rpc_client, payer) {
+                Ok(primed) => println!(
+                    "\nPrimed {} recipient accounts via {} in {:.2?}",
+                    primed,
+                    endpoints[0],
+                    started.elapsed()
+                ),
                 Err(err) => {
-                    result.set_error(format!("Transaction failed: {}", err));
+                    eprintln!("{}", err);
+                    std::process::exit(1);
                 }
             }
+        }
+        recipients = Some(generated);
+    }
 
-            result.complete();
-            result
-        });
-        handles.push(handle);
+    let presigned = match args.presigned {
+        Some(ref path) => match presigned::load(path) {
+            Ok(transactions) => transactions,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        },
+        None => vec![],
+    };
+    // Label results with the cluster each endpoint serves, and catch runs
+    // that accidentally mix clusters
+    let mut clusters = HashMap::new();
+    if !args.bench_get_block_height_only {
+        for endpoint in endpoints.iter().chain(&args.control_endpoint) {
+            if let Some(cluster) = cluster::detect(&RpcClient::new(endpoint.clone())) {
+                clusters.insert(endpoint.clone(), cluster);
+            }
+        }
+    }
+    let detected: BTreeSet<&str> = clusters.values().copied().collect();
+    let run_cluster = match detected.len() {
+        0 => None,
+        1 => detected.first().map(|cluster| cluster.to_string()),
+        _ => Some("mixed".to_string()),
+    };
+    if !args.quiet_success {
+        if let Some(ref cluster) = run_cluster {
+            println!("Cluster: {}", cluster);
+        }
+        // Pass it back as --seed to reproduce the run
+        println!("Seed: {}", seed);
+    }
+    if detected.len() > 1 {
+        println!("Warning: the endpoints serve different clusters:");
+        for endpoint in endpoints.iter().chain(&args.control_endpoint) {
+            if let Some(cluster) = clusters.get(endpoint) {
+                println!("  {}: {}", endpoint, cluster);
+            }
+        }
     }
 
-    // Collect all results
-    let results: Vec<BenchmarkResult> = handles
-        .into_iter()
-        .map(|handle| handle.join().unwrap())
-        .collect();
+    let replay = args.replay_schedule.as_ref().map(|path| {
+        let replay = match schedule::Schedule::load(path) {
+            Ok(replay) => replay,
+            Err(err) => {
+                eprintln!("Failed to read schedule {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        };
+        if replay.endpoints.len() != endpoints.len() {
+            eprintln!(
+                "{} was recorded against {} endpoints, but {} were given",
+                path.display(),
+                replay.endpoints.len(),
+                endpoints.len()
+            );
+            std::process::exit(1);
+        }
+        for (endpoint, recorded) in endpoints.iter().zip(&replay.endpoints) {
+            println!(
+                "Replaying the schedule of {} against {}",
+                recorded.recorded_from, endpoint
+            );
+        }
+        replay
+    });
+
+    // A replay runs as many iterations per endpoint as the schedule has for
+    // it. Schedules are recorded from runs with one count for every
+    // endpoint, so the highest stands for the run.
+    let iterations = match replay {
+        Some(ref replay) => (0..endpoints.len())
+            .map(|index| replay.iterations(index))
+            .max()
+            .unwrap_or_default(),
+        None => args.iterations,
+    };
+
+    if args.quiet_success {
+    } else if args.presigned.is_some() {
+        println!(
+            "\nSubmitting {} presigned transactions across {} endpoints...\n",
+            presigned.len(),
+            endpoints.len()
+        );
+    } else {
+        println!(
+            "\nStarting benchmark for {} endpoints ({} iterations each)...\n",
+            endpoints.len(),
+            iterations
+        );
+    }
+
+    let mut metadata = metadata::RunMetadata {
+        tool_version: env!("CARGO_PKG_VERSION"),
+        started_at: SystemTime::now(),
+        finished_at: None,
+        payer: keypair.as_ref().map(|keypair| keypair.pubkey().to_string()),
+        endpoint_count: endpoints.len(),
+        method,
+        iterations,
+        control_endpoint: args.control_endpoint.clone(),
+        confirm_via: args.confirm_via,
+        recipients: recipients.as_ref().map(|recipients| recipients.len()),
+        primed_accounts: args.prime_accounts && recipients.is_some(),
+        cluster: run_cluster,
+        tags: args.tags.iter().cloned().collect(),
+        seed,
+        benchmark_name: args.benchmark_name.clone(),
+        method_fallback: fallback.clone(),
+    };
+    let aliases = summary::endpoint_aliases(&endpoints);
+    let region_tags = region_tags.unwrap_or_default();
+    let summary_options = summary::SummaryOptions {
+        include_samples: args.include_samples,
+        slo_buckets: args.slo_buckets.clone().unwrap_or_default(),
+    };
+    let batch = args.batch.map(|size| {
+        let client = args
+            .http_version
+            .configure_blocking(reqwest::blocking::Client::builder())
+            .timeout(Duration::from_secs(args.request_timeout_secs))
+            .build()
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to build the batch HTTP client: {}", err);
+                std::process::exit(1);
+            });
+        batch::Batch {
+            size: size as usize,
+            client,
+        }
+    });
+    let config = Arc::new(BenchmarkConfig {
+        method,
+        minimal: args.bench_get_block_height_only,
+        // Only read methods that query an account use it, and those always
+        // have one of the two
+        account: args
+            .account
+            .or_else(|| keypair.as_ref().map(|keypair| keypair.pubkey()))
+            .unwrap_or_default(),
+        min_context_slot: args.min_context_slot,
+        min_context_slot_auto: args.min_context_slot_auto,
+        iterations,
+        sample_interval: Duration::from_millis(args.sample_interval_ms),
+        confirm_via: args.confirm_via,
+        poll_interval: Duration::from_millis(args.poll_interval_ms),
+        poll_adaptive: args.poll_adaptive,
+        confirm_timeout: Duration::from_secs(args.confirm_timeout_secs),
+        min_blockhash_validity: args.min_blockhash_validity,
+        require_commitment: args.require_commitment,
+        recipients,
+        presigned,
+        batch,
+        expect: methods::Expectations {
+            non_empty: args.expect_non_empty,
+            min_slot: args.expect_min_slot,
+        },
+        clusters,
+        health_probes,
+        tags: args.tags.iter().cloned().collect(),
+        benchmark_name: args.benchmark_name.clone(),
+        retry: retry::RetryPolicy {
+            retries: args.retries,
+            timeout: Duration::from_secs(args.request_timeout_secs),
+            timeout_multiplier: args.retry_timeout_multiplier,
+            http_version: args.http_version,
+            quiet: args.quiet_success,
+        },
+        started: Instant::now(),
+        replay,
+        fallback: fallback.clone(),
+        quiet: args.quiet_success,
+    });
+
+    // The control endpoint takes the slot after the benchmarked endpoints so
+    // its transfers stay unique too.
+    let endpoint_count = endpoints.len() as u64 + args.control_endpoint.is_some() as u64;
+
+    // With a control endpoint every thread waits at this barrier before each
+    // iteration, so the control's measurement runs concurrently with the rest.
+    let barrier = args
+        .control_endpoint
+        .as_ref()
+        .map(|_| Arc::new(Barrier::new(endpoint_count as usize)));
+
+    if let Some(ref levels) = args.parallelism_sweep {
+        sweep::run(&endpoints, keypair.as_deref(), &config, levels);
+        return;
+    }
+
+    if let Some(secs) = args.interval_secs {
+        let breaker = args
+            .breaker_threshold
+            .map(|threshold| interval::BreakerConfig {
+                threshold,
+                cooldown: args.breaker_cooldown,
+            });
+        let endpoint_results = interval::run(
+            &endpoints,
+            &aliases,
+            keypair.as_deref(),
+            &config,
+            interval::IntervalOptions {
+                interval: Duration::from_secs(secs),
+                cycles: args.cycles,
+                breaker,
+                sparkline: args.sparkline.map(|cycles| interval::SparklineConfig {
+                    cycles: cycles as usize,
+                    window: args.sparkline_window as usize,
+                }),
+            },
+        );
+        metadata.finished_at = Some(SystemTime::now());
+
+        let summaries = summarize(
+            &aliases,
+            &endpoints,
+            &region_tags,
+            &summary_options,
+            |index, _| {
+                endpoint_results[index]
+                    .iter()
+                    .map(summary::Sample::from)
+                    .collect()
+            },
+        );
+        let gate = args
+            .max_block_height_age
+            .map(|max_age| block_height_gate(&summaries, max_age));
+        report_summaries(args.format, &metadata, summaries, args.summary_json);
+        print_block_height_gate(&gate, args.quiet_success);
+        if gate.is_some_and(|(fresh, _)| !fresh) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let mut handles = vec![];
+
+    if let Some(workers) = args.workers {
+        handles = pool::spawn(
+            workers as usize,
+            &endpoints,
+            keypair.clone(),
+            Arc::clone(&config),
+            sender.clone(),
+        );
+    } else {
+        // Spawn a thread for each endpoint
+        for (endpoint_index, endpoint) in endpoints.iter().enumerate() {
+            handles.push(spawn_endpoint(
+                endpoint.clone(),
+                endpoint_index,
+                endpoint_count,
+                keypair.clone(),
+                Arc::clone(&config),
+                barrier.clone(),
+                sender.clone(),
+            ));
+        }
+    }
+
+    if let Some(endpoint) = args.control_endpoint.clone() {
+        handles.push(spawn_endpoint(
+            endpoint,
+            endpoints.len(),
+            endpoint_count,
+            keypair.clone(),
+            Arc::clone(&config),
+            barrier.clone(),
+            sender.clone(),
+        ));
+    }
+    drop(sender);
+
+    if let Some(path) = args.stream_to {
+        let totals = match stream::write(receiver, &path) {
+            Ok(totals) => totals,
+            Err(err) => {
+                eprintln!("Failed to stream results to {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        };
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        metadata.finished_at = Some(SystemTime::now());
+
+        println!(
+            "\nStreamed {} results ({} failed) to {}",
+            totals.results,
+            totals.failed,
+            path.display()
+        );
+
+        let mut samples = match stream::read_samples(&path) {
+            Ok(samples) => samples,
+            Err(err) => {
+                eprintln!("Failed to read back {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        };
+        let summaries = summarize(
+            &aliases,
+            &endpoints,
+            &region_tags,
+            &summary_options,
+            |_, endpoint| samples.remove(endpoint).unwrap_or_default(),
+        );
+
+        let gate = args
+            .max_block_height_age
+            .map(|max_age| block_height_gate(&summaries, max_age));
+        report_summaries(args.format, &metadata, summaries, args.summary_json);
+        print_block_height_gate(&gate, args.quiet_success);
+        if gate.is_some_and(|(fresh, _)| !fresh) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Collect all results, grouped per endpoint; anything past the last
+    // endpoint slot comes from the control endpoint
+    let mut endpoint_results: Vec<Vec<BenchmarkResult>> =
+        endpoints.iter().map(|_| vec![]).collect();
+    let mut control_results: Vec<BenchmarkResult> = vec![];
+    for (index, result) in receiver {
+        match endpoint_results.get_mut(index) {
+            Some(results) => results.push(result),
+            None => control_results.push(result),
+        }
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    metadata.finished_at = Some(SystemTime::now());
+
+    // Pool workers finish an endpoint's iterations out of order
+    if args.workers.is_some() {
+        for results in &mut endpoint_results {
+            results.sort_by_key(|result| result.iteration);
+        }
+    }
+
+    for results in &mut endpoint_results {
+        for (result, control) in results.iter_mut().zip(&control_results) {
+            result.normalize_against(control);
+        }
+    }
+
+    if let Some(ref path) = args.record_schedule {
+        let schedule =
+            schedule::Schedule::from_results(config.started, &aliases, &endpoint_results);
+        if let Err(err) = schedule.write(path) {
+            eprintln!("Failed to write schedule to {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+        config.progress(format!("\nSchedule written to {}", path.display()));
+    }
+
+    let mut landing_reports: BTreeMap<String, landing::LandingReport> = BTreeMap::new();
+    if let Some(secs) = args.verify_landing_after {
+        config.progress(format!(
+            "\nWaiting {}s before verifying that accepted transactions landed...",
+            secs
+        ));
+        thread::sleep(Duration::from_secs(secs));
+        for ((alias, endpoint), results) in
+            aliases.iter().zip(&endpoints).zip(&mut endpoint_results)
+        {
+            landing_reports.insert(alias.clone(), landing::verify(endpoint, results));
+        }
+    }
+
+    let drifts: Vec<drift::BlockHeightDrift> = if args.measure_block_height_drift {
+        endpoint_results
+            .iter()
+            .map(|results| drift::BlockHeightDrift::from_results(results))
+            .collect()
+    } else {
+        vec![]
+    };
+
+    let summaries = summarize(
+        &aliases,
+        &endpoints,
+        &region_tags,
+        &summary_options,
+        |index, _| {
+            endpoint_results[index]
+                .iter()
+                .map(summary::Sample::from)
+                .collect()
+        },
+    );
+
+    let budgets: BTreeMap<String, journey::LatencyBudget> = if args.latency_budget {
+        aliases
+            .iter()
+            .zip(&endpoint_results)
+            .map(|(alias, results)| (alias.clone(), journey::LatencyBudget::from_results(results)))
+            .collect()
+    } else {
+        BTreeMap::new()
+    };
+
+    let read_after_write = args
+        .min_context_slot_auto
+        .then(|| freshness::display(&aliases, &endpoint_results));
+
+    let fallback_table =
+        (!fallback.is_empty()).then(|| methods::display_fallback(&aliases, &endpoint_results));
+
+    let results: Vec<BenchmarkResult> = endpoint_results.into_iter().flatten().collect();
 
     // Display results
-    println!("\nBenchmark Results:");
-    println!("=================");
-    for (i, result) in results.iter().enumerate() {
-        println!("\nEndpoint #{}", i + 1);
-        println!("-----------");
-        print!("{}", result.display());
+    if !args.quiet_success {
+        print_benchmark_name(&metadata);
+    }
+    match args.format {
+        _ if args.quiet_success => print_failures(
+            args.format,
+            &results,
+            &endpoints,
+            &summaries,
+            args.benchmark_name.as_deref(),
+        ),
+        OutputFormat::Text => {
+            println!("\nBenchmark Results:");
+            println!("=================");
+            for result in &results {
+                let number = endpoints
+                    .iter()
+                    .position(|endpoint| *endpoint == result.endpoint)
+                    .unwrap_or_default()
+                    + 1;
+                if results.len() > endpoints.len() {
+                    println!(
+                        "\nEndpoint #{}, Iteration #{}",
+                        number,
+                        result.iteration + 1
+                    );
+                } else {
+                    println!("\nEndpoint #{}", number);
+                }
+                println!("-----------");
+                print!("{}", result.display());
+            }
+        }
+        OutputFormat::RegionMatrix => {
+            println!("\nRegion Matrix:");
+            println!("==============\n");
+            print!("{}", regions::display(&regions::region_matrix(&summaries)));
+        }
+    }
+
+    // The reports below only restate what succeeded, except for a failed
+    // freshness gate
+    if !args.quiet_success {
+        print_slo_buckets(&summaries);
+
+        if let Some(n) = args.top_slow {
+            print_slowest(&results, &endpoints, &aliases, n as usize);
+        }
+
+        if !control_results.is_empty() {
+            println!("\nControl Endpoint Results:");
+            println!("=========================");
+            for result in &control_results {
+                println!("\nIteration #{}", result.iteration + 1);
+                println!("-----------");
+                print!("{}", result.display());
+            }
+        }
+    }
+
+    let gate = args
+        .max_block_height_age
+        .map(|max_age| block_height_gate(&summaries, max_age));
+    print_block_height_gate(&gate, args.quiet_success);
+
+    if !args.quiet_success {
+        if !drifts.is_empty() {
+            println!("\nBlock Height Drift:");
+            println!("===================");
+            for drift in &drifts {
+                print!("\n{}", drift.display());
+            }
+        }
+
+        if let Some(ref table) = fallback_table {
+            println!("\nMethod Fallback:");
+            println!("================\n");
+            print!("{}", table);
+        }
+
+        if let Some(ref table) = read_after_write {
+            println!("\nRead-After-Write Consistency:");
+            println!("=============================\n");
+            print!("{}", table);
+        }
+
+        if !landing_reports.is_empty() {
+            println!("\nTransaction Landing:");
+            println!("====================\n");
+            print!("{}", landing::display(&landing_reports));
+        }
+
+        if !budgets.is_empty() {
+            println!("\nLatency Budget:");
+            println!("===============");
+            print!("{}", journey::display(&budgets));
+        }
+    }
+
+    if let Some(path) = args.json_output {
+        let report = json::JsonReport {
+            metadata: &metadata,
+            results: &results,
+            control_results: &control_results,
+            block_height_drift: &drifts,
+        };
+        write_json_or_exit(&path, &report);
+        if !args.quiet_success {
+            println!("\nJSON results written to {}", path.display());
+        }
+    }
+
+    if let Some(path) = args.summary_json {
+        let report = summary::SummaryReport {
+            metadata: &metadata,
+            endpoints: summaries,
+        };
+        write_json_or_exit(&path, &report);
+        if !args.quiet_success {
+            println!("\nJSON summary written to {}", path.display());
+        }
+    }
+
+    if gate.is_some_and(|(fresh, _)| !fresh) {
+        std::process::exit(1);
+    }
+}
+
+fn write_json_or_exit<T: Serialize>(path: &Path, value: &T) {
+    if let Err(err) = json::write(path, value) {
+        eprintln!("Failed to write JSON to {}: {}", path.display(), err);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tag_splits_key_and_value() {
+        assert_eq!(
+            parse_tag("env=prod").unwrap(),
+            ("env".to_string(), "prod".to_string())
+        );
+        assert_eq!(
+            parse_tag("query=a=b").unwrap(),
+            ("query".to_string(), "a=b".to_string())
+        );
+        assert_eq!(
+            parse_tag("_team=").unwrap(),
+            ("_team".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn parse_tag_rejects_invalid_keys() {
+        assert!(parse_tag("env").is_err());
+        assert!(parse_tag("=prod").is_err());
+        assert!(parse_tag("1env=prod").is_err());
+        assert!(parse_tag("env-name=prod").is_err());
+        assert!(parse_tag("__reserved=x").is_err());
+    }
+
+    fn summary_at(block_height: Option<u64>) -> summary::EndpointSummary {
+        let sample = summary::Sample {
+            duration_ms: Some(10.0),
+            error: None,
+            error_kind: None,
+            failed_after_retries: None,
+            block_height,
+        };
+        summary::EndpointSummary::from_samples("http://endpoint", &[sample])
+    }
+
+    #[test]
+    fn block_height_gate_passes_endpoints_within_the_max_age() {
+        let summaries = BTreeMap::from([
+            ("a".to_string(), summary_at(Some(100))),
+            ("b".to_string(), summary_at(Some(95))),
+        ]);
+        let (fresh, report) = block_height_gate(&summaries, 5);
+        assert!(fresh);
+        assert!(report.starts_with("PASSED"));
+    }
+
+    #[test]
+    fn block_height_gate_reports_lagging_and_silent_endpoints_apart() {
+        let summaries = BTreeMap::from([
+            ("a".to_string(), summary_at(Some(100))),
+            ("b".to_string(), summary_at(Some(90))),
+            ("c".to_string(), summary_at(None)),
+        ]);
+        let (fresh, report) = block_height_gate(&summaries, 5);
+        assert!(!fresh);
+        assert_eq!(
+            report,
+            concat!(
+                "FAILED: 1 of 3 endpoints are more than 5 blocks behind\n",
+                "  b: block height 90 is 10 behind 100\n",
+                "FAILED: 1 of 3 endpoints reported no block height\n",
+                "  c\n",
+            )
+        );
     }
 }
@@ -0,0 +1,165 @@
+use crate::json;
+use crate::summary::{self, EndpointSummary, Sample};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Args, Debug)]
+pub struct MergeArgs {
+    /// Result files written with --json-output
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// Write the merged results and unified summary to this file
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Source {
+    source: String,
+    file: String,
+    benchmark: Option<String>,
+    metadata: Value,
+}
+
+#[derive(Serialize)]
+struct MergedReport {
+    sources: Vec<Source>,
+    results: Vec<Value>,
+    // The sources that ran each --benchmark-name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    benchmarks: BTreeMap<String, Vec<String>>,
+    // Keyed by "<source>/<endpoint alias>" so the same endpoint measured from
+    // several vantage points stays distinct, under "<benchmark>/" for runs
+    // with a --benchmark-name
+    summary: BTreeMap<String, EndpointSummary>,
+}
+
+pub fn run(args: MergeArgs) {
+    let mut report = MergedReport {
+        sources: vec![],
+        results: vec![],
+        benchmarks: BTreeMap::new(),
+        summary: BTreeMap::new(),
+    };
+    let mut seen_sources: HashMap<String, usize> = HashMap::new();
+
+    for path in &args.files {
+        let mut file_report = match read_report(path) {
+            Ok(file_report) => file_report,
+            Err(err) => {
+                eprintln!("Failed to read {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        };
+
+        let metadata = file_report.remove("metadata").unwrap_or(Value::Null);
+        let benchmark = metadata
+            .get("benchmark_name")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let source = source_name(path, &mut seen_sources);
+        // Summary keys lead with the benchmark, so each profile's endpoints
+        // sort together across machines
+        let prefix = match benchmark {
+            Some(ref name) => format!("{}/{}", name, source),
+            None => source.clone(),
+        };
+        let results = match file_report.remove("results") {
+            Some(Value::Array(results)) => results,
+            _ => {
+                eprintln!("{} has no \"results\" array", path.display());
+                std::process::exit(1);
+            }
+        };
+
+        // Group samples per endpoint in order of first appearance
+        let mut endpoints: Vec<String> = vec![];
+        let mut samples: HashMap<String, Vec<Sample>> = HashMap::new();
+        for mut result in results {
+            let endpoint = result
+                .get("endpoint")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if !samples.contains_key(&endpoint) {
+                endpoints.push(endpoint.clone());
+            }
+            if let Ok(sample) = serde_json::from_value::<Sample>(result.clone()) {
+                samples.entry(endpoint).or_default().push(sample);
+            }
+            if let Value::Object(ref mut fields) = result {
+                fields.insert("source".to_string(), Value::String(source.clone()));
+            }
+            report.results.push(result);
+        }
+
+        for (alias, endpoint) in summary::endpoint_aliases(&endpoints)
+            .into_iter()
+            .zip(&endpoints)
+        {
+            let endpoint_samples = samples.remove(endpoint).unwrap_or_default();
+            report.summary.insert(
+                format!("{}/{}", prefix, alias),
+                EndpointSummary::from_samples(endpoint, &endpoint_samples),
+            );
+        }
+
+        if let Some(ref name) = benchmark {
+            report
+                .benchmarks
+                .entry(name.clone())
+                .or_default()
+                .push(source.clone());
+        }
+        report.sources.push(Source {
+            source,
+            file: path.display().to_string(),
+            benchmark,
+            metadata,
+        });
+    }
+
+    println!(
+        "Merged {} results from {} files\n",
+        report.results.len(),
+        report.sources.len()
+    );
+    print!("{}", summary::display_table(&report.summary));
+
+    if let Some(path) = args.output {
+        if let Err(err) = json::write(&path, &report) {
+            eprintln!("Failed to write JSON to {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+        println!("\nMerged results written to {}", path.display());
+    }
+}
+
+fn read_report(path: &Path) -> Result<Map<String, Value>, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    match serde_json::from_reader(BufReader::new(file)) {
+        Ok(Value::Object(report)) => Ok(report),
+        Ok(_) => Err("expected a JSON object".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+// Names a source after its file stem, suffixed when several files share one
+fn source_name(path: &Path, seen: &mut HashMap<String, usize>) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    let count = seen.entry(stem.clone()).or_insert(0);
+    *count += 1;
+    if *count == 1 {
+        stem
+    } else {
+        format!("{}-{}", stem, count)
+    }
+}
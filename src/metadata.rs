@@ -0,0 +1,33 @@
+use crate::confirm::ConfirmVia;
+use crate::json;
+use crate::methods::Method;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+// Describes the run as a whole; written at the top of every JSON output.
+#[derive(Debug, Serialize)]
+pub struct RunMetadata {
+    pub tool_version: &'static str,
+    #[serde(serialize_with = "json::system_time")]
+    pub started_at: SystemTime,
+    #[serde(serialize_with = "json::optional_system_time")]
+    pub finished_at: Option<SystemTime>,
+    pub payer: Option<String>,
+    pub endpoint_count: usize,
+    pub method: Method,
+    pub iterations: u32,
+    pub control_endpoint: Option<String>,
+    pub confirm_via: Option<ConfirmVia>,
+    pub recipients: Option<usize>,
+    pub primed_accounts: bool,
+    // The cluster all endpoints serve, or "mixed" if they disagree
+    pub cluster: Option<String>,
+    pub tags: BTreeMap<String, String>,
+    // Pass back as --seed to repeat the run's randomized choices
+    pub seed: u64,
+    pub benchmark_name: Option<String>,
+    // The chain `method` heads, with --method-fallback
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub method_fallback: Vec<Method>,
+}
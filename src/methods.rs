@@ -0,0 +1,542 @@
+use crate::BenchmarkResult;
+use crate::error::ErrorKind;
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Value, json};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED;
+use solana_client::rpc_request::{RpcError, RpcRequest};
+use solana_client::rpc_response::{Response, RpcSupply, RpcVersionInfo};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::epoch_info::EpochInfo;
+use solana_sdk::pubkey::Pubkey;
+use std::thread;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Method {
+    /// Send and confirm a self-transfer
+    #[default]
+    Transfer,
+    /// A transfer timed phase by phase: blockhash, simulate, send, confirm
+    Journey,
+    /// getBlockHeight
+    BlockHeight,
+    /// getSlot
+    Slot,
+    /// getBalance of --account
+    GetBalance,
+    /// getAccountInfo of --account
+    GetAccountInfo,
+    /// getSlot at processed and at finalized commitment, and the gap between
+    CommitmentLag,
+    /// getVersion
+    Version,
+    /// getEpochInfo
+    EpochInfo,
+    /// getHealth
+    Health,
+    /// getSupply, without the non-circulating account list
+    Supply,
+    /// getFirstAvailableBlock, and the history depth behind the current slot
+    FirstAvailableBlock,
+}
+
+// JSON-RPC "method not found"
+const METHOD_NOT_FOUND: i64 = -32601;
+
+pub const UNSUPPORTED: &str = "method unsupported on this node version";
+
+impl Method {
+    // The name --method takes
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .expect("no skipped variants")
+            .get_name()
+            .to_string()
+    }
+
+    pub fn is_read_only(self) -> bool {
+        !matches!(self, Method::Transfer | Method::Journey)
+    }
+
+    pub fn reads_account(self) -> bool {
+        matches!(self, Method::GetBalance | Method::GetAccountInfo)
+    }
+
+    // The RPC call a read method makes and its params
+    pub fn request(
+        self,
+        account: &Pubkey,
+        commitment: CommitmentConfig,
+        min_context_slot: Option<u64>,
+    ) -> (RpcRequest, Value) {
+        let mut config = json!({ "commitment": commitment.commitment });
+        if let Some(slot) = min_context_slot {
+            config["minContextSlot"] = json!(slot);
+        }
+        match self {
+            Method::Transfer | Method::Journey => unreachable!("transfers are not read methods"),
+            Method::BlockHeight => (RpcRequest::GetBlockHeight, json!([config])),
+            // commitment-lag makes this call once per commitment
+            Method::Slot | Method::CommitmentLag => (RpcRequest::GetSlot, json!([config])),
+            Method::GetBalance => (RpcRequest::GetBalance, json!([account.to_string(), config])),
+            Method::Version => (RpcRequest::GetVersion, Value::Null),
+            Method::Health => (RpcRequest::GetHealth, Value::Null),
+            // first-available-block pairs this with a getSlot for the depth
+            Method::FirstAvailableBlock => (RpcRequest::GetFirstAvailableBlock, Value::Null),
+            Method::EpochInfo => (RpcRequest::GetEpochInfo, json!([config])),
+            Method::Supply => {
+                // getSupply takes no minContextSlot
+                let config = json!({
+                    "commitment": commitment.commitment,
+                    "excludeNonCirculatingAccountsList": true,
+                });
+                (RpcRequest::GetSupply, json!([config]))
+            }
+            Method::GetAccountInfo => {
+                config["encoding"] = json!("base64");
+                (
+                    RpcRequest::GetAccountInfo,
+                    json!([account.to_string(), config]),
+                )
+            }
+        }
+    }
+
+    // Oldest solana-core release that serves every RPC call the method makes
+    fn min_version(self) -> (u64, u64, u64) {
+        match self {
+            // getLatestBlockhash and isBlockhashValid
+            Method::Transfer | Method::Journey => (1, 9, 0),
+            Method::BlockHeight => (1, 6, 0),
+            Method::Slot
+            | Method::GetBalance
+            | Method::GetAccountInfo
+            | Method::CommitmentLag
+            | Method::Version
+            | Method::EpochInfo
+            | Method::Health
+            | Method::Supply
+            | Method::FirstAvailableBlock => (1, 0, 0),
+        }
+    }
+
+    // Checks the node's getVersion against the compatibility map. Returns the
+    // reason the method can't run there, or None if it can (or the version is
+    // unknown).
+    pub fn unsupported_on(self, rpc_client: &RpcClient) -> Option<String> {
+        let version = rpc_client.get_version().ok()?;
+        let node = parse_version(&version.solana_core)?;
+        let (major, minor, patch) = self.min_version();
+        (node < (major, minor, patch)).then(|| {
+            format!(
+                "{} (node runs {}, {}.{}.{} or newer required)",
+                UNSUPPORTED, version.solana_core, major, minor, patch
+            )
+        })
+    }
+
+    // Whether the method records a block height for --max-block-height-age
+    pub fn reports_block_height(self) -> bool {
+        matches!(
+            self,
+            Method::Transfer | Method::Journey | Method::BlockHeight | Method::EpochInfo
+        )
+    }
+
+    // Whether the method's call can carry --min-context-slot. getVersion,
+    // getHealth and getFirstAvailableBlock take no config, and getSupply's
+    // has no minContextSlot.
+    pub fn takes_min_context_slot(self) -> bool {
+        !matches!(
+            self,
+            Method::Version | Method::Health | Method::Supply | Method::FirstAvailableBlock
+        )
+    }
+
+    // Whether the method reports a slot for --expect-min-slot to check
+    pub fn reports_slot(self) -> bool {
+        matches!(
+            self,
+            Method::Slot
+                | Method::GetBalance
+                | Method::GetAccountInfo
+                | Method::CommitmentLag
+                | Method::EpochInfo
+                | Method::Supply
+                | Method::FirstAvailableBlock
+        )
+    }
+
+    // The main value a successful read recorded
+    fn observed_value(self, result: &BenchmarkResult) -> Option<Value> {
+        let key = match self {
+            Method::Transfer | Method::Journey => unreachable!("transfers are not read methods"),
+            Method::BlockHeight => return result.block_height.map(Value::from),
+            Method::Slot | Method::EpochInfo => "slot",
+            Method::GetBalance => "lamports",
+            Method::GetAccountInfo => "account_found",
+            Method::CommitmentLag => "processed_slot",
+            Method::Version => "solana_core",
+            Method::Health => "health",
+            Method::Supply => "total_lamports",
+            Method::FirstAvailableBlock => "first_available_block",
+        };
+        result.extras.get(key).cloned()
+    }
+
+    // The slot a successful read was served at, if the method reports one
+    fn observed_slot(self, result: &BenchmarkResult) -> Option<u64> {
+        let key = match self {
+            Method::Slot | Method::EpochInfo | Method::FirstAvailableBlock => "slot",
+            Method::GetBalance | Method::GetAccountInfo | Method::Supply => "context_slot",
+            Method::CommitmentLag => "processed_slot",
+            _ => return None,
+        };
+        result.extras.get(key).and_then(Value::as_u64)
+    }
+}
+
+// Parses the leading "major.minor.patch" of a solana-core version string
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+// Runs a single read method call, recording its outcome on `result`.
+pub fn run_read(
+    method: Method,
+    rpc_client: &RpcClient,
+    account: &Pubkey,
+    min_context_slot: Option<u64>,
+    result: &mut BenchmarkResult,
+) {
+    let (request, params) = method.request(account, rpc_client.commitment(), min_context_slot);
+    let outcome = match method {
+        Method::Transfer | Method::Journey => unreachable!("transfers are not read methods"),
+        Method::BlockHeight => rpc_client
+            .send::<u64>(request, params)
+            .map(|height| result.set_block_height(height)),
+        Method::Slot => rpc_client
+            .send::<u64>(request, params)
+            .map(|slot| result.set_extra("slot", slot)),
+        Method::CommitmentLag => {
+            let (processed_request, processed_params) =
+                method.request(account, CommitmentConfig::processed(), min_context_slot);
+            let (finalized_request, finalized_params) =
+                method.request(account, CommitmentConfig::finalized(), min_context_slot);
+            // Both reads run at once so the gap isn't skewed by the time
+            // between them
+            let (processed, finalized) = thread::scope(|scope| {
+                let processed = scope.spawn(|| {
+                    rpc_client
+                        .send::<u64>(processed_request, processed_params)
+                        .map_err(Box::new)
+                });
+                let finalized = rpc_client.send::<u64>(finalized_request, finalized_params);
+                (processed.join().unwrap(), finalized)
+            });
+            match (processed, finalized) {
+                (Ok(processed), Ok(finalized)) => {
+                    result.set_extra("processed_slot", processed);
+                    result.set_extra("finalized_slot", finalized);
+                    result.set_extra("commitment_lag_slots", processed.saturating_sub(finalized));
+                    Ok(())
+                }
+                (Err(err), _) => Err(*err),
+                (_, Err(err)) => Err(err),
+            }
+        }
+        Method::FirstAvailableBlock => {
+            let (slot_request, slot_params) =
+                Method::Slot.request(account, rpc_client.commitment(), min_context_slot);
+            // The timed call is getFirstAvailableBlock; the slot read runs
+            // alongside it only to measure how much history the node keeps
+            let (first, slot) = thread::scope(|scope| {
+                let slot = scope.spawn(|| {
+                    rpc_client
+                        .send::<u64>(slot_request, slot_params)
+                        .map_err(Box::new)
+                });
+                let first = rpc_client.send::<u64>(request, params);
+                (first, slot.join().unwrap())
+            });
+            match (first, slot) {
+                (Ok(first), Ok(slot)) => {
+                    result.set_extra("first_available_block", first);
+                    result.set_extra("slot", slot);
+                    result.set_extra("history_depth_slots", slot.saturating_sub(first));
+                    Ok(())
+                }
+                (Err(err), _) => Err(err),
+                (_, Err(err)) => Err(*err),
+            }
+        }
+        Method::Version => rpc_client
+            .send::<RpcVersionInfo>(request, params)
+            .map(|version| result.set_extra("solana_core", version.solana_core)),
+        Method::Health => rpc_client
+            .send::<String>(request, params)
+            .map(|health| result.set_extra("health", health)),
+        Method::EpochInfo => rpc_client
+            .send::<EpochInfo>(request, params)
+            .map(|epoch_info| {
+                result.set_block_height(epoch_info.block_height);
+                result.set_extra("epoch", epoch_info.epoch);
+                result.set_extra("slot", epoch_info.absolute_slot);
+            }),
+        Method::Supply => rpc_client
+            .send::<Response<RpcSupply>>(request, params)
+            .map(|response| {
+                result.set_extra("context_slot", response.context.slot);
+                result.set_extra("total_lamports", response.value.total);
+            }),
+        Method::GetBalance => rpc_client
+            .send::<Response<u64>>(request, params)
+            .map(|response| {
+                result.set_extra("context_slot", response.context.slot);
+                result.set_extra("lamports", response.value);
+            }),
+        Method::GetAccountInfo => rpc_client
+            .send::<Response<Option<Value>>>(request, params)
+            .map(|response| {
+                result.set_extra("context_slot", response.context.slot);
+                result.set_extra("account_found", response.value.is_some());
+                if let Some(lamports) = response.value.as_ref().and_then(|a| a.get("lamports")) {
+                    result.set_extra("lamports", lamports.clone());
+                }
+            }),
+    };
+
+    if let Some(slot) = min_context_slot {
+        result.set_extra("min_context_slot", slot);
+        match outcome {
+            Ok(()) => result.set_extra("min_context_slot_outcome", "honored"),
+            Err(ref err) if is_min_context_slot_error(err) => {
+                result.set_extra("min_context_slot_outcome", "rejected")
+            }
+            Err(_) => {}
+        }
+    }
+
+    match outcome {
+        Err(ref err) if is_method_not_found(err) => {
+            result.set_error(ErrorKind::Unsupported, format!("{}: {}", UNSUPPORTED, err))
+        }
+        Err(err) => result.set_error(ErrorKind::of(&err), err.to_string()),
+        Ok(()) => {}
+    }
+}
+
+// Tries each method of the chain in turn until one succeeds. Every attempt
+// gets a fresh result, so the duration is the answering method's alone; the
+// methods tried before it are listed as `fallback_failed`, and the one that
+// answered as `fallback_method`. If none does, the last attempt's failure is
+// returned.
+pub fn run_fallback(
+    chain: &[Method],
+    rpc_client: &RpcClient,
+    account: &Pubkey,
+    min_context_slot: Option<u64>,
+    first: BenchmarkResult,
+) -> BenchmarkResult {
+    let mut failed: Vec<String> = vec![];
+    let mut attempt = first;
+    for (index, &method) in chain.iter().enumerate() {
+        if index > 0 {
+            attempt = BenchmarkResult::new(attempt.endpoint, attempt.iteration);
+        }
+        run_read(method, rpc_client, account, min_context_slot, &mut attempt);
+        attempt.complete();
+        if attempt.error.is_none() {
+            attempt.set_extra("fallback_method", method.name());
+            break;
+        }
+        failed.push(method.name());
+    }
+    if !failed.is_empty() {
+        attempt.set_extra("fallback_failed", failed);
+    }
+    attempt
+}
+
+// Per endpoint, how often each method of a --method-fallback chain was the
+// one that answered
+pub fn display_fallback(aliases: &[String], endpoint_results: &[Vec<BenchmarkResult>]) -> String {
+    let mut out = String::new();
+    for (alias, results) in aliases.iter().zip(endpoint_results) {
+        let mut answered: Vec<(&str, usize)> = vec![];
+        for method in results
+            .iter()
+            .filter_map(|result| result.extras.get("fallback_method"))
+            .filter_map(Value::as_str)
+        {
+            match answered.iter_mut().find(|(name, _)| *name == method) {
+                Some((_, count)) => *count += 1,
+                None => answered.push((method, 1)),
+            }
+        }
+        let failed = results.len() - answered.iter().map(|(_, count)| count).sum::<usize>();
+        let mut outcome: Vec<String> = answered
+            .iter()
+            .map(|(name, count)| format!("{} ({})", name, count))
+            .collect();
+        if failed > 0 {
+            outcome.push(format!("none answered ({})", failed));
+        }
+        out.push_str(&format!("{}: {}\n", alias, outcome.join(", ")));
+    }
+    out
+}
+
+fn is_method_not_found(err: &ClientError) -> bool {
+    matches!(
+        err.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == METHOD_NOT_FOUND
+    )
+}
+
+pub fn is_min_context_slot_error(err: &ClientError) -> bool {
+    matches!(
+        err.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+    )
+}
+
+// Extra conditions a successful read must meet to count as a success, so a
+// well-formed but wrong response (say, a null account that should exist)
+// still fails
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Expectations {
+    pub non_empty: bool,
+    pub min_slot: Option<u64>,
+}
+
+impl Expectations {
+    pub fn any(&self) -> bool {
+        self.non_empty || self.min_slot.is_some()
+    }
+
+    // Checks a successful read against the expectations, recording the
+    // outcome as `assertions` and failing the result if one doesn't hold
+    pub fn check(&self, method: Method, result: &mut BenchmarkResult) {
+        if !self.any() || result.error.is_some() {
+            return;
+        }
+        let mut failures = vec![];
+        if self.non_empty && is_empty(method.observed_value(result)) {
+            failures.push("expected a non-empty result".to_string());
+        }
+        if let Some(min_slot) = self.min_slot {
+            match method.observed_slot(result) {
+                Some(slot) if slot >= min_slot => {}
+                Some(slot) => failures.push(format!("expected slot >= {}, got {}", min_slot, slot)),
+                None => failures.push("expected a slot, got none".to_string()),
+            }
+        }
+        if failures.is_empty() {
+            result.set_extra("assertions", "passed");
+        } else {
+            result.set_extra("assertions", "failed");
+            result.set_error(ErrorKind::Assertion, failures.join("; "));
+        }
+    }
+}
+
+// Null, false, zero and the empty string all count as empty
+fn is_empty(value: Option<Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => true,
+        Some(Value::Number(number)) => number.as_f64() == Some(0.0),
+        Some(Value::String(string)) => string.is_empty(),
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version_reads_major_minor_and_patch() {
+        assert_eq!(parse_version("1.18.22"), Some((1, 18, 22)));
+        assert_eq!(parse_version("2.2.0-beta.1"), Some((2, 2, 0)));
+    }
+
+    #[test]
+    fn parse_version_rejects_incomplete_versions() {
+        assert_eq!(parse_version("1.18"), None);
+        assert_eq!(parse_version("unknown"), None);
+        assert_eq!(parse_version(""), None);
+    }
+
+    #[test]
+    fn is_empty_treats_null_false_zero_and_blank_as_empty() {
+        assert!(is_empty(None));
+        assert!(is_empty(Some(Value::Null)));
+        assert!(is_empty(Some(json!(false))));
+        assert!(is_empty(Some(json!(0))));
+        assert!(is_empty(Some(json!(""))));
+        assert!(!is_empty(Some(json!(true))));
+        assert!(!is_empty(Some(json!(5))));
+        assert!(!is_empty(Some(json!("ok"))));
+    }
+
+    fn balance_result(lamports: u64, context_slot: u64) -> BenchmarkResult {
+        let mut result = BenchmarkResult::new("http://endpoint".to_string(), 0);
+        result.set_extra("lamports", lamports);
+        result.set_extra("context_slot", context_slot);
+        result
+    }
+
+    #[test]
+    fn expectations_pass_a_matching_read() {
+        let expect = Expectations {
+            non_empty: true,
+            min_slot: Some(100),
+        };
+        let mut result = balance_result(5, 100);
+        expect.check(Method::GetBalance, &mut result);
+        assert!(result.error.is_none());
+        assert_eq!(result.extras["assertions"], "passed");
+    }
+
+    #[test]
+    fn expectations_fail_an_empty_or_stale_read() {
+        let expect = Expectations {
+            non_empty: true,
+            min_slot: Some(100),
+        };
+        let mut result = balance_result(0, 99);
+        expect.check(Method::GetBalance, &mut result);
+        assert_eq!(result.error_kind, Some(ErrorKind::Assertion));
+        assert_eq!(
+            result.error.as_deref(),
+            Some("expected a non-empty result; expected slot >= 100, got 99")
+        );
+        assert_eq!(result.extras["assertions"], "failed");
+    }
+
+    #[test]
+    fn expectations_leave_failed_reads_and_unset_checks_alone() {
+        let mut result = balance_result(0, 0);
+        Expectations::default().check(Method::GetBalance, &mut result);
+        assert!(result.error.is_none());
+        assert!(!result.extras.contains_key("assertions"));
+
+        let expect = Expectations {
+            non_empty: true,
+            min_slot: None,
+        };
+        result.set_error(ErrorKind::Timeout, "timed out".to_string());
+        expect.check(Method::GetBalance, &mut result);
+        assert_eq!(result.error_kind, Some(ErrorKind::Timeout));
+        assert!(!result.extras.contains_key("assertions"));
+    }
+}
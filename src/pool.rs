@@ -0,0 +1,103 @@
+use crate::{BenchmarkConfig, BenchmarkResult, run_iteration, unsupported_iteration};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::signature::Keypair;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+
+// Every endpoint x iteration pair, handed out in order to whichever worker
+// is free next. Tasks run iteration by iteration across all endpoints, so
+// each endpoint gets its turn before any endpoint gets a second one.
+struct TaskQueue {
+    endpoints: Vec<String>,
+    iterations: u32,
+    next: AtomicUsize,
+    // Connected on first use, so a big endpoint list doesn't open every
+    // client up front
+    clients: Vec<OnceLock<Endpoint>>,
+}
+
+struct Endpoint {
+    rpc_client: RpcClient,
+    unsupported: Option<String>,
+}
+
+impl TaskQueue {
+    // The next (endpoint index, iteration) to run, or None once drained
+    fn pop(&self) -> Option<(usize, u32)> {
+        let task = self.next.fetch_add(1, Ordering::Relaxed);
+        let iteration = task / self.endpoints.len();
+        (iteration < self.iterations as usize)
+            .then(|| (task % self.endpoints.len(), iteration as u32))
+    }
+
+    fn endpoint(&self, index: usize, config: &BenchmarkConfig) -> &Endpoint {
+        self.clients[index].get_or_init(|| {
+            let endpoint = &self.endpoints[index];
+            config.progress(format!("Connecting to {}", endpoint));
+            let rpc_client = config.retry.client(endpoint);
+            let unsupported = config.unsupported_on(&rpc_client);
+            Endpoint {
+                rpc_client,
+                unsupported,
+            }
+        })
+    }
+}
+
+// Starts `workers` threads that share one queue of every endpoint's
+// iterations, so the thread count stays fixed however many endpoints there
+// are. Results go to `results` tagged with their endpoint index, like the
+// per-endpoint threads'.
+pub fn spawn(
+    workers: usize,
+    endpoints: &[String],
+    keypair: Option<Arc<Keypair>>,
+    config: Arc<BenchmarkConfig>,
+    results: Sender<(usize, BenchmarkResult)>,
+) -> Vec<JoinHandle<()>> {
+    let queue = Arc::new(TaskQueue {
+        endpoints: endpoints.to_vec(),
+        iterations: config.iterations,
+        next: AtomicUsize::new(0),
+        clients: endpoints.iter().map(|_| OnceLock::new()).collect(),
+    });
+    let endpoint_count = endpoints.len() as u64;
+
+    (0..workers)
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let keypair = keypair.clone();
+            let config = Arc::clone(&config);
+            let results = results.clone();
+            thread::spawn(move || {
+                while let Some((endpoint_index, iteration)) = queue.pop() {
+                    let endpoint = &queue.endpoints[endpoint_index];
+                    let Endpoint {
+                        rpc_client,
+                        unsupported,
+                    } = queue.endpoint(endpoint_index, &config);
+                    // Same amounts as the per-endpoint threads use, so every
+                    // signature stays unique
+                    let lamports = iteration as u64 * endpoint_count + endpoint_index as u64 + 1;
+                    let mut result = match unsupported {
+                        Some(reason) => unsupported_iteration(endpoint, iteration, reason),
+                        None => config.retry.run(endpoint, rpc_client, |rpc_client| {
+                            run_iteration(
+                                endpoint,
+                                rpc_client,
+                                keypair.as_deref(),
+                                &config,
+                                iteration,
+                                lamports,
+                            )
+                        }),
+                    };
+                    config.label(&mut result);
+                    let _ = results.send((endpoint_index, result));
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,58 @@
+use crate::{BenchmarkConfig, BenchmarkResult, submit_transaction};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::transaction::Transaction;
+use std::fs;
+use std::path::Path;
+
+// Reads one base64-encoded, bincode-serialized transaction per line
+pub fn load(path: &Path) -> Result<Vec<Transaction>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let bytes = STANDARD.decode(line.trim()).map_err(|err| {
+                format!("{}:{}: invalid base64: {}", path.display(), index + 1, err)
+            })?;
+            bincode::deserialize(&bytes).map_err(|err| {
+                format!(
+                    "{}:{}: invalid transaction: {}",
+                    path.display(),
+                    index + 1,
+                    err
+                )
+            })
+        })
+        .collect()
+}
+
+// Submits one presigned transaction, timing only submission and
+// confirmation. Returns None when its blockhash has already expired, in
+// which case it is skipped rather than sent.
+pub fn submit(
+    endpoint: &str,
+    rpc_client: &RpcClient,
+    transaction: &Transaction,
+    config: &BenchmarkConfig,
+    iteration: u32,
+) -> Option<BenchmarkResult> {
+    let blockhash = transaction.message.recent_blockhash;
+    if let Ok(false) = rpc_client.is_blockhash_valid(&blockhash, rpc_client.commitment()) {
+        println!(
+            "{}: skipping presigned transaction {}, its blockhash {} has expired",
+            endpoint, transaction.signatures[0], blockhash
+        );
+        return None;
+    }
+
+    let mut result = BenchmarkResult::new(endpoint.to_string(), iteration);
+    result.set_extra("blockhash", blockhash.to_string());
+    result.set_extra("presigned", true);
+    submit_transaction(endpoint, rpc_client, transaction, config, &mut result);
+    result.complete();
+    Some(result)
+}
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use chrono::DateTime;
+use clap::ValueEnum;
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response, Version};
+use reqwest_middleware::{Middleware, Next};
+use serde::Serialize;
+use std::cell::Cell;
+use std::time::{Duration, SystemTime};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize)]
+pub enum HttpVersion {
+    /// HTTP/1.1 only
+    #[value(name = "1.1")]
+    #[serde(rename = "1.1")]
+    Http1,
+    /// HTTP/2 only, assumed without negotiation (prior knowledge)
+    #[value(name = "2")]
+    #[serde(rename = "2")]
+    Http2,
+    /// Whatever the endpoint negotiates: HTTP/2 when offered over TLS,
+    /// HTTP/1.1 otherwise
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+}
+
+impl HttpVersion {
+    pub fn configure(self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        match self {
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2 => builder.http2_prior_knowledge(),
+            HttpVersion::Auto => builder,
+        }
+    }
+
+    // For requests sent with the blocking client directly, like batches
+    pub fn configure_blocking(
+        self,
+        builder: reqwest::blocking::ClientBuilder,
+    ) -> reqwest::blocking::ClientBuilder {
+        match self {
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2 => builder.http2_prior_knowledge(),
+            HttpVersion::Auto => builder,
+        }
+    }
+}
+
+fn name(version: Version) -> &'static str {
+    match version {
+        Version::HTTP_09 => "HTTP/0.9",
+        Version::HTTP_10 => "HTTP/1.0",
+        Version::HTTP_11 => "HTTP/1.1",
+        Version::HTTP_2 => "HTTP/2",
+        Version::HTTP_3 => "HTTP/3",
+        _ => "unknown",
+    }
+}
+
+// What a response said about the connection and
+// the server, beyond its JSON-RPC body
+#[derive(Clone, Copy, Debug)]
+pub struct ResponseInfo {
+    pub version: &'static str,
+    // The server's Date header, truncated to the second like the header is
+    pub server_date: Option<SystemTime>,
+    // How far the server's clock runs ahead of ours (negative if behind),
+    // estimated from the Date header against the request's midpoint
+    pub clock_offset_ms: Option<f64>,
+}
+
+thread_local! {
+    // The latest response a benchmark client got on this thread. Blocking
+    // RpcClient runs every request on the calling thread, so this is the
+    // response of the iteration running there, whatever runs elsewhere.
+    static LAST_RESPONSE: Cell<Option<ResponseInfo>> = const { Cell::new(None) };
+}
+
+// Takes the response recorded on this thread since the last call, if any
+pub fn take_last_response() -> Option<ResponseInfo> {
+    LAST_RESPONSE.take()
+}
+
+// Middleware recording each response of a client. RpcClient never hands its
+// responses out, so this sits in its middleware stack instead.
+pub struct Recorder;
+
+#[async_trait]
+impl Middleware for Recorder {
+    async fn handle(
+        &self,
+        request: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let sent = SystemTime::now();
+        let response = next.run(request, extensions).await?;
+        LAST_RESPONSE.set(Some(ResponseInfo::of(
+            response.version(),
+            response.headers(),
+            sent,
+            SystemTime::now(),
+        )));
+        Ok(response)
+    }
+}
+
+impl ResponseInfo {
+    pub fn of(
+        version: Version,
+        headers: &HeaderMap,
+        sent: SystemTime,
+        received: SystemTime,
+    ) -> Self {
+        let server_date = headers
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+            .map(SystemTime::from);
+        // The server stamped the header somewhere between our send and our
+        // receive, so compare against the midpoint. The header drops the
+        // fraction of a second, which half a second makes up for on average.
+        let midpoint = sent + received.duration_since(sent).unwrap_or_default() / 2;
+        let clock_offset_ms = server_date.map(|date| {
+            let date = date + Duration::from_millis(500);
+            match date.duration_since(midpoint) {
+                Ok(ahead) => ahead.as_secs_f64() * 1000.0,
+                Err(behind) => -behind.duration().as_secs_f64() * 1000.0,
+            }
+        });
+        ResponseInfo {
+            version: name(version),
+            server_date,
+            clock_offset_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{DATE, HeaderValue};
+    use std::time::UNIX_EPOCH;
+
+    // 1000s after the epoch, answered 2s later: the midpoint is at 1001s
+    fn info(date: Option<&'static str>) -> ResponseInfo {
+        let mut headers = HeaderMap::new();
+        if let Some(date) = date {
+            headers.insert(DATE, HeaderValue::from_static(date));
+        }
+        let sent = UNIX_EPOCH + Duration::from_secs(1000);
+        ResponseInfo::of(
+            Version::HTTP_2,
+            &headers,
+            sent,
+            sent + Duration::from_secs(2),
+        )
+    }
+
+    #[test]
+    fn clock_offset_compares_the_date_against_the_midpoint() {
+        // 1003s, plus half a second for the truncated fraction
+        let info = info(Some("Thu, 01 Jan 1970 00:16:43 GMT"));
+        assert_eq!(info.version, "HTTP/2");
+        assert_eq!(
+            info.server_date,
+            Some(UNIX_EPOCH + Duration::from_secs(1003))
+        );
+        assert_eq!(info.clock_offset_ms, Some(2500.0));
+    }
+
+    #[test]
+    fn clock_offset_is_negative_for_a_server_behind_us() {
+        let info = info(Some("Thu, 01 Jan 1970 00:16:39 GMT"));
+        assert_eq!(info.clock_offset_ms, Some(-1500.0));
+    }
+
+    #[test]
+    fn missing_or_malformed_dates_give_no_offset() {
+        assert_eq!(info(None).clock_offset_ms, None);
+        let info = info(Some("yesterday"));
+        assert_eq!(info.server_date, None);
+        assert_eq!(info.clock_offset_ms, None);
+    }
+}
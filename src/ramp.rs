@@ -0,0 +1,248 @@
+use crate::stats::percentile;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+
+// A level counts as degraded once more than this share of sends fail...
+const MAX_ERROR_RATE: f64 = 0.05;
+// ...or once the endpoint accepts less than this share of the target rate.
+const MIN_ACHIEVED_RATIO: f64 = 0.9;
+// Blockhashes expire after ~60-90s, so long steps need a fresh one.
+const BLOCKHASH_REFRESH: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+struct RampLevel {
+    target_tps: u64,
+    sent: u64,
+    failed: u64,
+    elapsed: Duration,
+    latencies: Vec<Duration>,
+}
+
+impl RampLevel {
+    fn succeeded(&self) -> u64 {
+        self.sent - self.failed
+    }
+
+    fn achieved_tps(&self) -> f64 {
+        self.succeeded() as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn error_rate(&self) -> f64 {
+        if self.sent == 0 {
+            return 0.0;
+        }
+        self.failed as f64 / self.sent as f64
+    }
+
+    fn p95(&self) -> Option<Duration> {
+        let mut sorted = self.latencies.clone();
+        sorted.sort();
+        percentile(&sorted, 95.0)
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.error_rate() > MAX_ERROR_RATE
+            || self.achieved_tps() < self.target_tps as f64 * MIN_ACHIEVED_RATIO
+    }
+}
+
+#[derive(Debug)]
+struct RampResult {
+    endpoint: String,
+    levels: Vec<RampLevel>,
+    error: Option<String>,
+}
+
+impl RampResult {
+    fn display(&self) -> String {
+        let mut out = format!("Endpoint: {}\n", self.endpoint);
+        out.push_str(&format!(
+            "{:>10} {:>8} {:>10} {:>8} {:>13} {:>11} {:>12}  {}\n",
+            "Target TPS",
+            "Sent",
+            "Succeeded",
+            "Failed",
+            "Achieved TPS",
+            "Error Rate",
+            "p95 Latency",
+            "Outcome"
+        ));
+        for level in &self.levels {
+            let p95 = level
+                .p95()
+                .map(|d| format!("{:.2?}", d))
+                .unwrap_or_else(|| "N/A".to_string());
+            let outcome = if level.is_degraded() {
+                "DEGRADED"
+            } else {
+                "OK"
+            };
+            out.push_str(&format!(
+                "{:>10} {:>8} {:>10} {:>8} {:>13.1} {:>10.1}% {:>12}  {}\n",
+                level.target_tps,
+                level.sent,
+                level.succeeded(),
+                level.failed,
+                level.achieved_tps(),
+                level.error_rate() * 100.0,
+                p95,
+                outcome
+            ));
+        }
+
+        match self.levels.iter().find(|level| level.is_degraded()) {
+            Some(level) => out.push_str(&format!("Breaking point: {} TPS\n", level.target_tps)),
+            None => {
+                let max = self.levels.last().map(|l| l.target_tps).unwrap_or(0);
+                out.push_str(&format!("No breaking point found up to {} TPS\n", max));
+            }
+        }
+
+        if let Some(ref error) = self.error {
+            out.push_str(&format!("Error Details: {}\n", error));
+        }
+        out
+    }
+}
+
+pub fn run(endpoints: Vec<String>, keypair: Arc<Keypair>, levels: &[u64], step: Duration) {
+    println!(
+        "\nStarting TPS ramp for {} endpoints ({} levels, {:?} per level)...\n",
+        endpoints.len(),
+        levels.len(),
+        step
+    );
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let results: Vec<RampResult> = runtime.block_on(async {
+        let endpoint_count = endpoints.len() as u64;
+        let mut handles = vec![];
+        for (index, endpoint) in endpoints.into_iter().enumerate() {
+            let keypair = Arc::clone(&keypair);
+            let levels = levels.to_vec();
+            handles.push(tokio::spawn(ramp_endpoint(
+                endpoint,
+                keypair,
+                levels,
+                step,
+                index as u64,
+                endpoint_count,
+            )));
+        }
+
+        let mut results = vec![];
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        results
+    });
+
+    println!("\nTPS Ramp Results:");
+    println!("=================");
+    for (i, result) in results.iter().enumerate() {
+        println!("\nEndpoint #{}", i + 1);
+        println!("-----------");
+        print!("{}", result.display());
+    }
+}
+
+async fn ramp_endpoint(
+    endpoint: String,
+    keypair: Arc<Keypair>,
+    levels: Vec<u64>,
+    step: Duration,
+    endpoint_index: u64,
+    endpoint_count: u64,
+) -> RampResult {
+    let rpc_client = Arc::new(RpcClient::new(endpoint.clone()));
+    let mut result = RampResult {
+        endpoint: endpoint.clone(),
+        levels: vec![],
+        error: None,
+    };
+
+    // Every endpoint sends self-transfers from the same keypair, so the
+    // lamport amount doubles as a nonce to keep signatures unique across
+    // endpoints and within a blockhash window.
+    let mut sequence = 0u64;
+
+    for target_tps in levels {
+        if target_tps == 0 {
+            continue;
+        }
+        println!("{}: ramping to {} TPS", endpoint, target_tps);
+
+        let mut blockhash = match rpc_client.get_latest_blockhash().await {
+            Ok(blockhash) => blockhash,
+            Err(err) => {
+                result.error = Some(format!("Failed to get blockhash: {}", err));
+                return result;
+            }
+        };
+        let mut blockhash_fetched = Instant::now();
+
+        let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / target_tps as f64));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+
+        let mut tasks = JoinSet::new();
+        let mut sent = 0u64;
+        let started = Instant::now();
+        while started.elapsed() < step {
+            ticker.tick().await;
+
+            if blockhash_fetched.elapsed() > BLOCKHASH_REFRESH {
+                if let Ok(fresh) = rpc_client.get_latest_blockhash().await {
+                    blockhash = fresh;
+                }
+                blockhash_fetched = Instant::now();
+            }
+
+            sequence += 1;
+            let lamports = sequence * endpoint_count + endpoint_index;
+            let transaction = self_transfer(&keypair, lamports, blockhash);
+            let rpc_client = Arc::clone(&rpc_client);
+            tasks.spawn(async move {
+                let sent_at = Instant::now();
+                let outcome = rpc_client.send_transaction(&transaction).await;
+                (sent_at.elapsed(), outcome.is_ok())
+            });
+            sent += 1;
+        }
+        let elapsed = started.elapsed();
+
+        let mut level = RampLevel {
+            target_tps,
+            sent,
+            failed: 0,
+            elapsed,
+            latencies: vec![],
+        };
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((latency, true)) => level.latencies.push(latency),
+                _ => level.failed += 1,
+            }
+        }
+        result.levels.push(level);
+    }
+
+    result
+}
+
+fn self_transfer(keypair: &Keypair, lamports: u64, blockhash: Hash) -> Transaction {
+    let instruction = system_instruction::transfer(&keypair.pubkey(), &keypair.pubkey(), lamports);
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&keypair.pubkey()),
+        &[keypair],
+        blockhash,
+    )
+}
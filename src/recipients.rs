@@ -0,0 +1,68 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+
+// Transfers per priming transaction, well within the transaction size limit
+const PRIME_BATCH: usize = 8;
+
+#[derive(Debug)]
+pub struct Recipients {
+    addresses: Vec<Pubkey>,
+    // Added to every transfer until the accounts are primed, so the first
+    // transfer to each fresh address can cover its rent-exempt minimum.
+    extra_lamports: u64,
+}
+
+impl Recipients {
+    // Nothing is ever signed by a recipient, so bare random addresses do;
+    // drawing them from `rng` makes them repeat under the same seed.
+    pub fn generate(count: usize, rent_exempt_minimum: u64, rng: &mut StdRng) -> Self {
+        Recipients {
+            addresses: (0..count)
+                .map(|_| Pubkey::new_from_array(rng.r#gen()))
+                .collect(),
+            extra_lamports: rent_exempt_minimum,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    // Picks the recipient for a transfer and the amount to send it. The
+    // recipients are cycled through in order of the transfer's sequence.
+    pub fn transfer(&self, sequence: u64) -> (Pubkey, u64) {
+        let index = (sequence as usize) % self.addresses.len();
+        (self.addresses[index], sequence + self.extra_lamports)
+    }
+
+    // Creates every recipient account up front by funding it with the
+    // rent-exempt minimum, so timed transfers all go to existing accounts.
+    pub fn prime(&mut self, rpc_client: &RpcClient, payer: &Keypair) -> Result<usize, String> {
+        for batch in self.addresses.chunks(PRIME_BATCH) {
+            let instructions: Vec<_> = batch
+                .iter()
+                .map(|to| system_instruction::transfer(&payer.pubkey(), to, self.extra_lamports))
+                .collect();
+            let blockhash = rpc_client
+                .get_latest_blockhash()
+                .map_err(|err| format!("Failed to get blockhash: {}", err))?;
+            let transaction = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &[payer],
+                blockhash,
+            );
+            rpc_client
+                .send_and_confirm_transaction(&transaction)
+                .map_err(|err| format!("Failed to prime recipient accounts: {}", err))?;
+        }
+        self.extra_lamports = 0;
+        Ok(self.addresses.len())
+    }
+}
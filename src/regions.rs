@@ -0,0 +1,90 @@
+use crate::summary::EndpointSummary;
+use std::collections::BTreeMap;
+
+const UNTAGGED: &str = "untagged";
+
+// One row of the region x metric matrix, aggregated over the region's
+// endpoint summaries.
+#[derive(Debug)]
+pub struct RegionRow {
+    pub region: String,
+    pub endpoints: usize,
+    // Mean of the member endpoints' p50 durations
+    pub p50_ms: Option<f64>,
+    pub success_rate: f64,
+    // Mean distance of the member endpoints' latest block height from the
+    // highest block height observed across all endpoints
+    pub blocks_behind: Option<f64>,
+}
+
+pub fn region_matrix(summaries: &BTreeMap<String, EndpointSummary>) -> Vec<RegionRow> {
+    let max_height = summaries
+        .values()
+        .filter_map(|s| s.latest_block_height)
+        .max();
+
+    let mut by_region: BTreeMap<&str, Vec<&EndpointSummary>> = BTreeMap::new();
+    for summary in summaries.values() {
+        let region = summary.region.as_deref().unwrap_or(UNTAGGED);
+        by_region.entry(region).or_default().push(summary);
+    }
+
+    by_region
+        .into_iter()
+        .map(|(region, members)| {
+            let p50s: Vec<f64> = members.iter().filter_map(|s| s.p50_ms).collect();
+            let lags: Vec<f64> = members
+                .iter()
+                .filter_map(|s| Some(max_height?.saturating_sub(s.latest_block_height?) as f64))
+                .collect();
+            let samples: usize = members.iter().map(|s| s.samples).sum();
+            let successes: usize = members.iter().map(|s| s.successes).sum();
+
+            RegionRow {
+                region: region.to_string(),
+                endpoints: members.len(),
+                p50_ms: mean(&p50s),
+                success_rate: if samples == 0 {
+                    0.0
+                } else {
+                    successes as f64 / samples as f64
+                },
+                blocks_behind: mean(&lags),
+            }
+        })
+        .collect()
+}
+
+pub fn display(rows: &[RegionRow]) -> String {
+    let mut out = format!(
+        "{:<20} {:>9} {:>12} {:>13} {:>14}\n",
+        "Region", "Endpoints", "p50 Latency", "Success Rate", "Blocks Behind"
+    );
+    for row in rows {
+        let p50 = row
+            .p50_ms
+            .map(|ms| format!("{:.2}ms", ms))
+            .unwrap_or_else(|| "N/A".to_string());
+        let behind = row
+            .blocks_behind
+            .map(|blocks| format!("{:.1}", blocks))
+            .unwrap_or_else(|| "N/A".to_string());
+        out.push_str(&format!(
+            "{:<20} {:>9} {:>12} {:>12.1}% {:>14}\n",
+            row.region,
+            row.endpoints,
+            p50,
+            row.success_rate * 100.0,
+            behind
+        ));
+    }
+    out
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
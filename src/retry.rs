@@ -0,0 +1,112 @@
+use crate::BenchmarkResult;
+use crate::error::ErrorKind;
+use crate::protocol::{HttpVersion, Recorder};
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::time::Duration;
+
+// Scaled timeouts stop growing here, well short of where a Duration or the
+// timer behind it would overflow
+const MAX_TIMEOUT: Duration = Duration::from_secs(3600);
+
+// --retry-timeout-multiplier only makes sense as a finite factor of at least
+// 1: anything less would shrink the timeout of a request that just timed out
+pub fn parse_multiplier(value: &str) -> Result<f64, String> {
+    let multiplier: f64 = value
+        .parse()
+        .map_err(|_| format!("expected a number, got {:?}", value))?;
+    if !multiplier.is_finite() || multiplier < 1.0 {
+        return Err(format!(
+            "expected a finite factor of at least 1, got {}",
+            value
+        ));
+    }
+    Ok(multiplier)
+}
+
+#[derive(Debug)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub timeout: Duration,
+    // Applied to the timeout after each attempt that timed out
+    pub timeout_multiplier: f64,
+    pub http_version: HttpVersion,
+    // Set by --quiet-success, which keeps retry notices off the console
+    pub quiet: bool,
+}
+
+impl RetryPolicy {
+    pub fn client(&self, endpoint: &str) -> RpcClient {
+        self.client_with_timeout(endpoint, self.timeout)
+    }
+
+    // What RpcClient::new_with_timeout builds, but speaking --http-version
+    // and recording the protocol each response came over
+    fn client_with_timeout(&self, endpoint: &str, timeout: Duration) -> RpcClient {
+        let client = self
+            .http_version
+            .configure(reqwest::Client::builder())
+            .default_headers(HttpSender::default_headers())
+            .timeout(timeout)
+            .pool_idle_timeout(timeout)
+            .build()
+            .expect("build rpc client");
+        let client = reqwest_middleware::ClientBuilder::new(client)
+            .with(Recorder)
+            .build();
+        RpcClient::new_sender(
+            HttpSender::new_with_client_with_middleware(endpoint, client),
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+        )
+    }
+
+    // Runs `attempt` until it succeeds, fails in a way that retrying won't
+    // fix, or the retries are used up. Timeouts stretch the next attempt's
+    // timeout, which tells a slow endpoint apart from a dead one.
+    pub fn run(
+        &self,
+        endpoint: &str,
+        rpc_client: &RpcClient,
+        mut attempt: impl FnMut(&RpcClient) -> BenchmarkResult,
+    ) -> BenchmarkResult {
+        let mut timeout = self.timeout;
+        let mut scaled_client = None;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let mut result = attempt(scaled_client.as_ref().unwrap_or(rpc_client));
+            let retryable = matches!(
+                result.error_kind,
+                Some(ErrorKind::Timeout | ErrorKind::RateLimited | ErrorKind::Connection)
+            );
+            if !retryable || attempts > self.retries {
+                if self.retries > 0 {
+                    result.set_extra("attempts", attempts);
+                    if result.error.is_none() {
+                        result.set_extra("final_timeout_ms", timeout.as_secs_f64() * 1000.0);
+                    } else if attempts > 1 {
+                        result.failed_after_retries = Some(attempts - 1);
+                    }
+                }
+                return result;
+            }
+
+            if result.error_kind == Some(ErrorKind::Timeout) && self.timeout_multiplier != 1.0 {
+                timeout =
+                    Duration::try_from_secs_f64(timeout.as_secs_f64() * self.timeout_multiplier)
+                        .map_or(MAX_TIMEOUT, |scaled| scaled.min(MAX_TIMEOUT));
+                scaled_client = Some(self.client_with_timeout(endpoint, timeout));
+            }
+            if !self.quiet {
+                println!(
+                    "{}: attempt {} failed ({}), retrying with a {:?} timeout",
+                    endpoint,
+                    attempts,
+                    result.error_kind.unwrap_or(ErrorKind::Other),
+                    timeout
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::BenchmarkResult;
+use crate::json;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// When each request of a run was issued, as offsets from the run start, so
+// a later run against other endpoints can issue its requests at the same
+// moments relative to its own start.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Schedule {
+    pub endpoints: Vec<EndpointSchedule>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EndpointSchedule {
+    // Alias of the endpoint the schedule was recorded against, for reference
+    pub recorded_from: String,
+    pub offsets_ms: Vec<f64>,
+}
+
+impl Schedule {
+    pub fn from_results(
+        started: Instant,
+        aliases: &[String],
+        endpoint_results: &[Vec<BenchmarkResult>],
+    ) -> Self {
+        let endpoints = aliases
+            .iter()
+            .zip(endpoint_results)
+            .map(|(alias, results)| {
+                let mut results: Vec<&BenchmarkResult> = results.iter().collect();
+                results.sort_by_key(|result| result.iteration);
+                EndpointSchedule {
+                    recorded_from: alias.clone(),
+                    offsets_ms: results
+                        .iter()
+                        .map(|result| {
+                            result
+                                .start_time
+                                .saturating_duration_since(started)
+                                .as_secs_f64()
+                                * 1000.0
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        Schedule { endpoints }
+    }
+
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        json::write(path, self)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|err| err.to_string())?;
+        serde_json::from_reader(BufReader::new(file)).map_err(|err| err.to_string())
+    }
+
+    // Number of iterations the endpoint in this slot replays
+    pub fn iterations(&self, endpoint_index: usize) -> u32 {
+        self.endpoints
+            .get(endpoint_index)
+            .map_or(0, |endpoint| endpoint.offsets_ms.len() as u32)
+    }
+
+    // Sleeps until the recorded issue time of this iteration. Iterations
+    // that fall behind the schedule start right away.
+    pub fn wait(&self, started: Instant, endpoint_index: usize, iteration: u32) {
+        let offset = self.endpoints[endpoint_index].offsets_ms[iteration as usize];
+        let due = started + Duration::from_secs_f64(offset / 1000.0);
+        thread::sleep(due.saturating_duration_since(Instant::now()));
+    }
+}
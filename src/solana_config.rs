@@ -0,0 +1,30 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The fields of the Solana CLI's config.yml this tool uses. The file is read
+// directly: solana-cli-config pulls in the hardware wallet stack, which
+// needs libudev just to build.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SolanaConfig {
+    pub json_rpc_url: Option<String>,
+    pub keypair_path: Option<PathBuf>,
+}
+
+// Where the `solana` CLI keeps its config unless told otherwise
+pub fn default_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(Path::new(&home).join(".config/solana/cli/config.yml"))
+}
+
+pub fn load(path: &Path) -> Result<SolanaConfig, String> {
+    let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let config: SolanaConfig = serde_yaml::from_str(&contents).map_err(|err| err.to_string())?;
+    Ok(SolanaConfig {
+        json_rpc_url: config.json_rpc_url.filter(|url| !url.is_empty()),
+        keypair_path: config
+            .keypair_path
+            .filter(|path| !path.as_os_str().is_empty()),
+    })
+}
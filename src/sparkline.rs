@@ -0,0 +1,41 @@
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// Renders values as unicode block heights scaled between their minimum and
+// maximum; gaps (None) render as spaces.
+pub fn render(values: &[Option<f64>]) -> String {
+    let present = values.iter().flatten();
+    let min = present.clone().copied().fold(f64::INFINITY, f64::min);
+    let max = present.copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|value| match value {
+            None => ' ',
+            Some(_) if max <= min => BLOCKS[BLOCKS.len() / 2],
+            Some(value) => {
+                let scaled = (value - min) / (max - min) * (BLOCKS.len() - 1) as f64;
+                BLOCKS[scaled.round() as usize]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_scales_between_the_minimum_and_maximum() {
+        assert_eq!(render(&[Some(10.0), Some(20.0), Some(80.0)]), "▁▂█");
+    }
+
+    #[test]
+    fn render_leaves_gaps_for_missing_values() {
+        assert_eq!(render(&[Some(1.0), None, Some(2.0)]), "▁ █");
+    }
+
+    #[test]
+    fn render_draws_flat_values_at_mid_height() {
+        assert_eq!(render(&[Some(5.0), Some(5.0)]), "▅▅");
+        assert_eq!(render(&[None, None]), "  ");
+    }
+}
@@ -0,0 +1,28 @@
+// Returns the p-th percentile (0-100) of an ascending-sorted slice using the
+// nearest-rank method.
+pub fn percentile<T: Copy>(sorted: &[T], p: f64) -> Option<T> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_uses_the_nearest_rank() {
+        let sorted = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_eq!(percentile(&sorted, 50.0), Some(5));
+        assert_eq!(percentile(&sorted, 95.0), Some(10));
+        assert_eq!(percentile(&sorted, 0.0), Some(1));
+        assert_eq!(percentile(&sorted, 100.0), Some(10));
+    }
+
+    #[test]
+    fn percentile_of_nothing_is_none() {
+        assert_eq!(percentile::<f64>(&[], 50.0), None);
+    }
+}
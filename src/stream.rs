@@ -0,0 +1,58 @@
+use crate::BenchmarkResult;
+use crate::summary::Sample;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+
+#[derive(Debug, Default)]
+pub struct StreamTotals {
+    pub results: usize,
+    pub failed: usize,
+}
+
+#[derive(Deserialize)]
+struct StreamedSample {
+    endpoint: String,
+    #[serde(flatten)]
+    sample: Sample,
+}
+
+// Writes each result as one JSON line as soon as it arrives, so only running
+// totals stay in memory no matter how large the run is.
+pub fn write(
+    receiver: Receiver<(usize, BenchmarkResult)>,
+    path: &Path,
+) -> io::Result<StreamTotals> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut totals = StreamTotals::default();
+    for (_, result) in receiver {
+        serde_json::to_writer(&mut writer, &result)?;
+        writer.write_all(b"\n")?;
+        totals.results += 1;
+        if result.error.is_some() {
+            totals.failed += 1;
+        }
+    }
+    writer.flush()?;
+    Ok(totals)
+}
+
+// Reads the streamed file back, keeping only the fields summaries need
+pub fn read_samples(path: &Path) -> io::Result<HashMap<String, Vec<Sample>>> {
+    let mut samples: HashMap<String, Vec<Sample>> = HashMap::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let streamed: StreamedSample = serde_json::from_str(&line)?;
+        samples
+            .entry(streamed.endpoint)
+            .or_default()
+            .push(streamed.sample);
+    }
+    Ok(samples)
+}
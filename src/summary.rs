@@ -0,0 +1,236 @@
+use crate::BenchmarkResult;
+use crate::error::ErrorKind;
+use crate::metadata::RunMetadata;
+use crate::stats::percentile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use url::Url;
+
+// Per-endpoint aggregates over all iterations. Latency figures only cover
+// successful iterations.
+#[derive(Clone, Debug, Serialize)]
+pub struct EndpointSummary {
+    pub endpoint: String,
+    pub region: Option<String>,
+    pub samples: usize,
+    pub successes: usize,
+    pub success_rate: f64,
+    pub mean_ms: Option<f64>,
+    pub p50_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub errors: BTreeMap<ErrorKind, usize>,
+    // The part of `errors` that persisted through every retry
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub failed_after_retries: BTreeMap<ErrorKind, usize>,
+    pub latest_block_height: Option<u64>,
+    // Every iteration's duration in iteration order, null where it failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_durations_ms: Option<Vec<Option<f64>>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub slo: Vec<SloBucket>,
+}
+
+// Share of all iterations, failed ones included, that succeeded within
+// `threshold_ms`
+#[derive(Clone, Debug, Serialize)]
+pub struct SloBucket {
+    pub threshold_ms: u64,
+    pub fraction: f64,
+}
+
+// Optional extras computed while summarizing
+#[derive(Debug, Default)]
+pub struct SummaryOptions {
+    pub include_samples: bool,
+    pub slo_buckets: Vec<u64>,
+}
+
+// The fields of a result that summaries are computed from. It deserializes
+// straight from a result in the JSON output, so merged result files can be
+// re-aggregated without the original `BenchmarkResult`s.
+#[derive(Debug, Deserialize)]
+pub struct Sample {
+    pub duration_ms: Option<f64>,
+    pub error: Option<String>,
+    pub error_kind: Option<ErrorKind>,
+    #[serde(default)]
+    pub failed_after_retries: Option<u32>,
+    pub block_height: Option<u64>,
+}
+
+impl From<&BenchmarkResult> for Sample {
+    fn from(result: &BenchmarkResult) -> Self {
+        Self {
+            duration_ms: result.duration_ms,
+            error: result.error.clone(),
+            error_kind: result.error_kind,
+            failed_after_retries: result.failed_after_retries,
+            block_height: result.block_height,
+        }
+    }
+}
+
+impl EndpointSummary {
+    pub fn from_samples(endpoint: &str, samples: &[Sample]) -> Self {
+        let mut durations: Vec<f64> = samples
+            .iter()
+            .filter(|s| s.error.is_none())
+            .filter_map(|s| s.duration_ms)
+            .collect();
+        durations.sort_by(f64::total_cmp);
+
+        let mut errors = BTreeMap::new();
+        let mut failed_after_retries = BTreeMap::new();
+        for sample in samples.iter().filter(|s| s.error.is_some()) {
+            let kind = sample.error_kind.unwrap_or(ErrorKind::Other);
+            *errors.entry(kind).or_insert(0) += 1;
+            if sample.failed_after_retries.is_some() {
+                *failed_after_retries.entry(kind).or_insert(0) += 1;
+            }
+        }
+
+        let successes = samples.len() - errors.values().sum::<usize>();
+        let mean_ms = if durations.is_empty() {
+            None
+        } else {
+            Some(durations.iter().sum::<f64>() / durations.len() as f64)
+        };
+
+        Self {
+            endpoint: endpoint.to_string(),
+            region: None,
+            samples: samples.len(),
+            successes,
+            success_rate: if samples.is_empty() {
+                0.0
+            } else {
+                successes as f64 / samples.len() as f64
+            },
+            mean_ms,
+            p50_ms: percentile(&durations, 50.0),
+            p95_ms: percentile(&durations, 95.0),
+            p99_ms: percentile(&durations, 99.0),
+            errors,
+            failed_after_retries,
+            latest_block_height: samples.iter().filter_map(|s| s.block_height).max(),
+            sample_durations_ms: None,
+            slo: vec![],
+        }
+    }
+
+    pub fn with_options(mut self, samples: &[Sample], options: &SummaryOptions) -> Self {
+        if options.include_samples {
+            self.include_samples(samples);
+        }
+        self.slo = options
+            .slo_buckets
+            .iter()
+            .map(|&threshold_ms| {
+                let within = samples
+                    .iter()
+                    .filter(|s| s.error.is_none())
+                    .filter(|s| s.duration_ms.is_some_and(|ms| ms < threshold_ms as f64))
+                    .count();
+                SloBucket {
+                    threshold_ms,
+                    fraction: if samples.is_empty() {
+                        0.0
+                    } else {
+                        within as f64 / samples.len() as f64
+                    },
+                }
+            })
+            .collect();
+        self
+    }
+
+    fn include_samples(&mut self, samples: &[Sample]) {
+        self.sample_durations_ms = Some(
+            samples
+                .iter()
+                .map(|s| s.duration_ms.filter(|_| s.error.is_none()))
+                .collect(),
+        );
+    }
+}
+
+pub fn display_table(summaries: &BTreeMap<String, EndpointSummary>) -> String {
+    let width = summaries.keys().map(String::len).max().unwrap_or(0).max(8);
+    let mut out = format!(
+        "{:<width$} {:>8} {:>9} {:>10} {:>10} {:>10} {:>10}\n",
+        "Endpoint", "Samples", "Success", "Mean", "p50", "p95", "p99"
+    );
+    for (key, summary) in summaries {
+        let millis = |value: Option<f64>| {
+            value
+                .map(|ms| format!("{:.2}ms", ms))
+                .unwrap_or_else(|| "N/A".to_string())
+        };
+        out.push_str(&format!(
+            "{:<width$} {:>8} {:>8.1}% {:>10} {:>10} {:>10} {:>10}\n",
+            key,
+            summary.samples,
+            summary.success_rate * 100.0,
+            millis(summary.mean_ms),
+            millis(summary.p50_ms),
+            millis(summary.p95_ms),
+            millis(summary.p99_ms)
+        ));
+    }
+    out
+}
+
+pub fn display_slo_table(summaries: &BTreeMap<String, EndpointSummary>) -> String {
+    let width = summaries.keys().map(String::len).max().unwrap_or(0).max(8);
+    let Some(first) = summaries.values().next() else {
+        return String::new();
+    };
+    let mut out = format!("{:<width$}", "Endpoint");
+    for bucket in &first.slo {
+        out.push_str(&format!(" {:>10}", format!("<{}ms", bucket.threshold_ms)));
+    }
+    out.push('\n');
+    for (key, summary) in summaries {
+        out.push_str(&format!("{:<width$}", key));
+        for bucket in &summary.slo {
+            out.push_str(&format!(" {:>9.1}%", bucket.fraction * 100.0));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[derive(Serialize)]
+pub struct SummaryReport<'a> {
+    pub metadata: &'a RunMetadata,
+    pub endpoints: BTreeMap<String, EndpointSummary>,
+}
+
+// Short, key-free names for endpoints: the host (plus any explicit port),
+// suffixed with a counter when several endpoints share one.
+pub fn endpoint_aliases(endpoints: &[String]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    endpoints
+        .iter()
+        .map(|endpoint| {
+            let base = Url::parse(endpoint)
+                .ok()
+                .and_then(|url| {
+                    let host = url.host_str()?.to_string();
+                    Some(match url.port() {
+                        Some(port) => format!("{}:{}", host, port),
+                        None => host,
+                    })
+                })
+                .unwrap_or_else(|| endpoint.clone());
+            let count = seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                base
+            } else {
+                format!("{}-{}", base, count)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,201 @@
+use crate::stats::percentile;
+use crate::{BenchmarkConfig, BenchmarkResult, run_iteration};
+use solana_sdk::signature::Keypair;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A level counts as degraded once more than this share of requests fail...
+const MAX_ERROR_RATE: f64 = 0.05;
+// ...or once its p95 grows past this multiple of the lowest level's.
+const MAX_P95_GROWTH: f64 = 2.0;
+
+#[derive(Debug)]
+struct SweepLevel {
+    concurrency: u64,
+    requests: usize,
+    failed: usize,
+    elapsed: Duration,
+    // Successful iterations only, sorted
+    durations_ms: Vec<f64>,
+}
+
+impl SweepLevel {
+    fn from_results(concurrency: u64, results: &[BenchmarkResult], elapsed: Duration) -> Self {
+        let mut durations_ms: Vec<f64> = results
+            .iter()
+            .filter(|result| result.error.is_none())
+            .filter_map(|result| result.duration_ms)
+            .collect();
+        durations_ms.sort_by(f64::total_cmp);
+        SweepLevel {
+            concurrency,
+            requests: results.len(),
+            failed: results
+                .iter()
+                .filter(|result| result.error.is_some())
+                .count(),
+            elapsed,
+            durations_ms,
+        }
+    }
+
+    fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        self.failed as f64 / self.requests as f64
+    }
+
+    fn throughput(&self) -> f64 {
+        (self.requests - self.failed) as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn p50(&self) -> Option<f64> {
+        percentile(&self.durations_ms, 50.0)
+    }
+
+    fn p95(&self) -> Option<f64> {
+        percentile(&self.durations_ms, 95.0)
+    }
+
+    fn is_degraded(&self, baseline_p95: Option<f64>) -> bool {
+        let p95_grew = match (self.p95(), baseline_p95) {
+            (Some(p95), Some(baseline)) => p95 > baseline * MAX_P95_GROWTH,
+            _ => false,
+        };
+        self.error_rate() > MAX_ERROR_RATE || p95_grew || self.durations_ms.is_empty()
+    }
+}
+
+#[derive(Debug)]
+struct SweepResult {
+    endpoint: String,
+    levels: Vec<SweepLevel>,
+}
+
+impl SweepResult {
+    fn display(&self) -> String {
+        let mut out = format!("Endpoint: {}\n", self.endpoint);
+        out.push_str(&format!(
+            "{:>11} {:>9} {:>11} {:>13} {:>10} {:>10}  {}\n",
+            "Concurrency", "Requests", "Error Rate", "Throughput/s", "p50", "p95", "Outcome"
+        ));
+        let baseline_p95 = self.levels.first().and_then(SweepLevel::p95);
+        let millis = |value: Option<f64>| {
+            value
+                .map(|ms| format!("{:.2}ms", ms))
+                .unwrap_or_else(|| "N/A".to_string())
+        };
+        for level in &self.levels {
+            let outcome = if level.is_degraded(baseline_p95) {
+                "DEGRADED"
+            } else {
+                "OK"
+            };
+            out.push_str(&format!(
+                "{:>11} {:>9} {:>10.1}% {:>13.1} {:>10} {:>10}  {}\n",
+                level.concurrency,
+                level.requests,
+                level.error_rate() * 100.0,
+                level.throughput(),
+                millis(level.p50()),
+                millis(level.p95()),
+                outcome
+            ));
+        }
+
+        // The knee is the first degraded level; the one before it is the
+        // highest concurrency the endpoint handled cleanly
+        let knee = self
+            .levels
+            .iter()
+            .position(|level| level.is_degraded(baseline_p95));
+        match knee {
+            Some(0) => out.push_str("Degraded at the lowest concurrency level\n"),
+            Some(index) => out.push_str(&format!(
+                "Practical concurrency limit: {} (degrades at {})\n",
+                self.levels[index - 1].concurrency,
+                self.levels[index].concurrency
+            )),
+            None => {
+                let max = self.levels.last().map(|l| l.concurrency).unwrap_or(0);
+                out.push_str(&format!("No degradation found up to {} concurrent\n", max));
+            }
+        }
+        out
+    }
+}
+
+// Benchmarks the configured method against each endpoint at every
+// concurrency level in turn: `level` workers each run `config.iterations`
+// iterations at once. Endpoints are swept one after another so they don't
+// compete for this machine's bandwidth.
+pub fn run(
+    endpoints: &[String],
+    keypair: Option<&Keypair>,
+    config: &BenchmarkConfig,
+    levels: &[u64],
+) {
+    println!(
+        "\nStarting parallelism sweep for {} endpoints ({} levels, {} iterations per worker)...\n",
+        endpoints.len(),
+        levels.len(),
+        config.iterations
+    );
+    // Transfers vary their amount to keep every signature unique
+    let sequence = AtomicU64::new(0);
+
+    let results: Vec<SweepResult> = endpoints
+        .iter()
+        .map(|endpoint| {
+            let rpc_client = config.retry.client(endpoint);
+            let levels = levels
+                .iter()
+                .map(|&concurrency| {
+                    println!("{}: {} concurrent", endpoint, concurrency);
+                    let started = Instant::now();
+                    let results: Vec<BenchmarkResult> = thread::scope(|scope| {
+                        let workers: Vec<_> = (0..concurrency)
+                            .map(|_| {
+                                scope.spawn(|| {
+                                    (0..config.iterations)
+                                        .map(|iteration| {
+                                            let lamports =
+                                                sequence.fetch_add(1, Ordering::Relaxed) + 1;
+                                            run_iteration(
+                                                endpoint,
+                                                &rpc_client,
+                                                keypair,
+                                                config,
+                                                iteration,
+                                                lamports,
+                                            )
+                                        })
+                                        .collect::<Vec<_>>()
+                                })
+                            })
+                            .collect();
+                        workers
+                            .into_iter()
+                            .flat_map(|worker| worker.join().unwrap())
+                            .collect()
+                    });
+                    SweepLevel::from_results(concurrency, &results, started.elapsed())
+                })
+                .collect();
+            SweepResult {
+                endpoint: endpoint.clone(),
+                levels,
+            }
+        })
+        .collect();
+
+    println!("\nParallelism Sweep Results:");
+    println!("==========================");
+    for (i, result) in results.iter().enumerate() {
+        println!("\nEndpoint #{}", i + 1);
+        println!("-----------");
+        print!("{}", result.display());
+    }
+}
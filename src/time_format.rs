@@ -0,0 +1,51 @@
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeFormat {
+    /// RFC 3339 in UTC, unambiguous across regions
+    Rfc3339,
+    /// Seconds since the Unix epoch
+    Unix,
+    /// Human-readable local time
+    #[default]
+    Local,
+}
+
+// Chosen once from the CLI so the console and every structured output
+// format timestamps the same way.
+static TIME_FORMAT: OnceLock<TimeFormat> = OnceLock::new();
+
+impl TimeFormat {
+    pub fn install(self) {
+        let _ = TIME_FORMAT.set(self);
+    }
+
+    pub fn current() -> Self {
+        TIME_FORMAT.get().copied().unwrap_or_default()
+    }
+
+    pub fn format(self, time: SystemTime) -> String {
+        match self {
+            TimeFormat::Rfc3339 => {
+                let datetime: DateTime<Utc> = time.into();
+                datetime.to_rfc3339_opts(SecondsFormat::Millis, true)
+            }
+            TimeFormat::Unix => format!("{:.3}", unix_seconds(time)),
+            TimeFormat::Local => {
+                let datetime: DateTime<Local> = time.into();
+                datetime.format("%Y-%m-%d %H:%M:%S.%3f %Z").to_string()
+            }
+        }
+    }
+}
+
+pub fn unix_seconds(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}